@@ -1,29 +1,193 @@
 #[allow(unused_imports)]
 use proc_macro::TokenStream;
-use quote::quote;
-use syn::{parse_macro_input, Item};
+use quote::{format_ident, quote};
+use rustantic::attributes::{ContainerAttributes, FieldAttributes};
+use rustantic::marcos::{has_pyo3_getter, is_option_type};
+use syn::{parse_macro_input, spanned::Spanned, Fields, Item, ItemStruct};
+
+/// Validates the field level `#[pydantic(...)]` attributes and removes them,
+/// they are only read by the build time collector.
+fn strip_field_attrs(fields: &mut Fields, add_getters: bool) -> syn::Result<Vec<FieldAttributes>> {
+    let mut result = Vec::with_capacity(fields.len());
+    for (idx, field) in fields.iter_mut().enumerate() {
+        let field_attrs = FieldAttributes::from_attrs(&field.attrs)?;
+        if field_attrs.skip && field.ident.is_none() {
+            return Err(syn::Error::new_spanned(
+                &field.ty,
+                "`skip` is not supported on tuple fields",
+            ));
+        }
+        field.attrs.retain(|attr| !attr.path().is_ident("pydantic"));
+
+        // Generated `from_rs()` reads the fields from the Python object,
+        // tuple fields use the `_0` naming pyo3 uses for tuple variants.
+        // Spanned on the type so a type pyo3 can't convert is reported on the field
+        if add_getters && !field_attrs.skip && !has_pyo3_getter(field) {
+            let span = field.ty.span();
+            if field.ident.is_some() {
+                field
+                    .attrs
+                    .push(syn::parse_quote_spanned!(span=> #[pyo3(get)]));
+            } else {
+                let name = format!("_{}", idx);
+                field
+                    .attrs
+                    .push(syn::parse_quote_spanned!(span=> #[pyo3(get, name = #name)]));
+            }
+        }
+        result.push(field_attrs);
+    }
+    Ok(result)
+}
+
+/// Generates the `#[new]` constructor of `#[pydantic(new)]` structs, skipped fields use `Default`.
+/// Named fields are keyword-only arguments, tuple fields positional arguments.
+fn generate_constructor(
+    item_struct: &ItemStruct,
+    field_attrs: &[FieldAttributes],
+) -> proc_macro2::TokenStream {
+    let mut args = vec![];
+    let mut signature = vec![];
+    let mut inits = vec![];
+    for (idx, (field, attrs)) in item_struct.fields.iter().zip(field_attrs).enumerate() {
+        let arg = field
+            .ident
+            .clone()
+            .unwrap_or_else(|| format_ident!("field_{}", idx));
+        let init = match field.ident {
+            Some(ref ident) if attrs.skip => {
+                inits.push(quote!(#ident: ::std::default::Default::default()));
+                continue;
+            }
+            Some(ref ident) => quote!(#ident: #arg),
+            None => quote!(#arg),
+        };
+
+        let ty = &field.ty;
+        args.push(quote!(#arg: #ty));
+        if field.ident.is_some() && is_option_type(ty) {
+            signature.push(quote!(#arg = None));
+        } else {
+            signature.push(quote!(#arg));
+        }
+        inits.push(init);
+    }
+
+    let body = match item_struct.fields {
+        Fields::Named(_) => {
+            signature.insert(0, quote!(*));
+            quote!(Self { #(#inits),* })
+        }
+        Fields::Unnamed(_) => quote!(Self(#(#inits),*)),
+        Fields::Unit => quote!(Self),
+    };
+
+    let ident = &item_struct.ident;
+    quote! {
+        #[pymethods]
+        impl #ident {
+            #[new]
+            #[pyo3(signature = (#(#signature),*))]
+            fn __rustantic_new(#(#args),*) -> Self {
+                #body
+            }
+        }
+    }
+}
+
+fn pyclass_args(attrs: &ContainerAttributes) -> proc_macro2::TokenStream {
+    let mut args = vec![];
+    if let Some(ref name) = attrs.name {
+        args.push(quote!(name = #name));
+    }
+    if let Some(ref module) = attrs.module {
+        args.push(quote!(module = #module));
+    }
+
+    if args.is_empty() {
+        quote!()
+    } else {
+        quote!((#(#args),*))
+    }
+}
 
 #[proc_macro_attribute]
-pub fn pydantic(_attr: TokenStream, item: TokenStream) -> TokenStream {
-    let input = parse_macro_input!(item as Item);
+pub fn pydantic(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let attrs = parse_macro_input!(attr as ContainerAttributes);
+    let mut input = parse_macro_input!(item as Item);
+    let pyclass_args = pyclass_args(&attrs);
+    let stripped = match input {
+        Item::Struct(ref mut item_struct) => {
+            strip_field_attrs(&mut item_struct.fields, attrs.get_all)
+        }
+        Item::Enum(ref mut item_enum) => item_enum
+            .variants
+            .iter_mut()
+            .map(|variant| strip_field_attrs(&mut variant.fields, false))
+            .collect::<syn::Result<Vec<_>>>()
+            .map(|_| vec![]),
+        _ => Ok(vec![]),
+    };
+    let field_attrs = match stripped {
+        Ok(field_attrs) => field_attrs,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
     let output = match input {
         Item::Struct(item_struct) => {
+            let constructor = if attrs.new {
+                generate_constructor(&item_struct, &field_attrs)
+            } else {
+                quote!()
+            };
             quote! {
-                #[pyclass]
+                #[pyclass #pyclass_args]
                 #item_struct
+                #constructor
             }
         }
+        Item::Enum(ref item_enum) if attrs.new || attrs.get_all => {
+            let arg = if attrs.new { "new" } else { "get_all" };
+            return syn::Error::new_spanned(
+                &item_enum.ident,
+                format!("#[pydantic({})] can only be used on structs", arg),
+            )
+            .to_compile_error()
+            .into();
+        }
         Item::Enum(item_enum) => {
-            if item_enum.variants.iter().all(|f| f.fields.is_empty()) {
+            if item_enum
+                .variants
+                .iter()
+                .all(|f| matches!(f.fields, Fields::Unit))
+            {
                 quote! {
-                    #[pyclass]
+                    #[pyclass #pyclass_args]
                     #[pyo3(eq, eq_int)]
                     #[derive(PartialEq)]
                     #item_enum
                 }
             } else {
+                // pyo3 only supports payload-less variants of complex enums as `Variant()`
+                let unit_variant = item_enum
+                    .variants
+                    .iter()
+                    .find(|variant| matches!(variant.fields, Fields::Unit));
+                if let Some(variant) = unit_variant {
+                    let ident = &variant.ident;
+                    return syn::Error::new_spanned(
+                        ident,
+                        format!(
+                            "unit variants aren't supported in an enum with data, declare it as \
+                             `{}()`",
+                            ident
+                        ),
+                    )
+                    .to_compile_error()
+                    .into();
+                }
                 quote! {
-                    #[pyclass]
+                    #[pyclass #pyclass_args]
                     #[derive(PartialEq)]
                     #item_enum
                 }
@@ -0,0 +1,10 @@
+use rustantic_macros::pydantic;
+
+#[pydantic]
+pub struct Range {
+    #[pydantic(ge = 1)]
+    #[pydantic(ge = 2)]
+    pub start: u32,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use rustantic_macros::pydantic;
+
+#[pydantic(get_all)]
+pub enum Color {
+    Red,
+    Green,
+}
+
+fn main() {}
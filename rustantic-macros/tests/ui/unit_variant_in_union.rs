@@ -0,0 +1,9 @@
+use rustantic_macros::pydantic;
+
+#[pydantic]
+pub enum Cmd {
+    Say(String),
+    Stop,
+}
+
+fn main() {}
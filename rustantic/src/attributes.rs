@@ -0,0 +1,292 @@
+use syn::{
+    parse::{Parse, ParseStream},
+    Attribute, Ident, Lit, LitStr, Meta, Token,
+};
+
+/// Value of the `extra` argument, mirrors pydantic's `ConfigDict(extra=...)`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExtraFields {
+    Allow,
+    Ignore,
+    Forbid,
+}
+
+impl ExtraFields {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExtraFields::Allow => "allow",
+            ExtraFields::Ignore => "ignore",
+            ExtraFields::Forbid => "forbid",
+        }
+    }
+}
+
+/// Arguments of the item level `#[pydantic(...)]` attribute, e.g.
+/// `#[pydantic(name = "User", frozen, extra = "forbid", module = "my_pkg.users")]`.
+#[derive(Clone, Debug, Default)]
+pub struct ContainerAttributes {
+    /// Name of the generated model and of the exposed pyclass.
+    pub name: Option<String>,
+    /// Generate an immutable model (`ConfigDict(frozen=True)`).
+    pub frozen: bool,
+    /// How the model treats unknown fields.
+    pub extra: Option<ExtraFields>,
+    /// Python module exposing the pyclass, defaults to the package name.
+    pub module: Option<String>,
+    /// Let the macro generate the `#[new]` constructor from the struct fields.
+    pub new: bool,
+    /// Let the macro add `#[pyo3(get)]` to the struct fields, `from_rs()` reads them.
+    pub get_all: bool,
+}
+
+impl ContainerAttributes {
+    const KNOWN_ARGS: &'static str = "`name`, `frozen`, `extra`, `module`, `new`, `get_all`";
+
+    /// Parses the arguments of every `#[pydantic(...)]` attribute in `attrs`.
+    pub fn from_attrs(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut result = Self::default();
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("pydantic")) {
+            if let Meta::List(_) = attr.meta {
+                attr.parse_args_with(|input: ParseStream| result.parse_args(input))?;
+            }
+        }
+        Ok(result)
+    }
+
+    /// Adds the arguments of one `#[pydantic(...)]` attribute, arguments already set by a
+    /// previous attribute are duplicates too.
+    fn parse_args(&mut self, input: ParseStream) -> syn::Result<()> {
+        while !input.is_empty() {
+            let key: Ident = input.parse()?;
+            match key.to_string().as_str() {
+                "name" => {
+                    let value = parse_str_value(input, &key)?;
+                    set_once(&mut self.name, value.value(), &key)?;
+                }
+                "module" => {
+                    let value = parse_str_value(input, &key)?;
+                    set_once(&mut self.module, value.value(), &key)?;
+                }
+                "frozen" => {
+                    if self.frozen {
+                        return Err(duplicate_arg(&key));
+                    }
+                    self.frozen = true;
+                }
+                "new" => {
+                    if self.new {
+                        return Err(duplicate_arg(&key));
+                    }
+                    self.new = true;
+                }
+                "get_all" => {
+                    if self.get_all {
+                        return Err(duplicate_arg(&key));
+                    }
+                    self.get_all = true;
+                }
+                "extra" => {
+                    let value = parse_str_value(input, &key)?;
+                    let extra = match value.value().as_str() {
+                        "allow" => ExtraFields::Allow,
+                        "ignore" => ExtraFields::Ignore,
+                        "forbid" => ExtraFields::Forbid,
+                        _ => {
+                            return Err(syn::Error::new_spanned(
+                                value,
+                                "expected one of \"allow\", \"ignore\" or \"forbid\"",
+                            ))
+                        }
+                    };
+                    set_once(&mut self.extra, extra, &key)?;
+                }
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        &key,
+                        format!(
+                            "unknown #[pydantic] argument `{}`, expected one of {}",
+                            key,
+                            Self::KNOWN_ARGS
+                        ),
+                    ))
+                }
+            }
+
+            if input.is_empty() {
+                break;
+            }
+            input.parse::<Token![,]>()?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Parse for ContainerAttributes {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut result = Self::default();
+        result.parse_args(input)?;
+        Ok(result)
+    }
+}
+
+/// Value of a field constraint, numbers keep their Rust literal representation.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConstraintValue {
+    Number(String),
+    Str(String),
+}
+
+/// Arguments of the field level `#[pydantic(...)]` attribute, e.g.
+/// `#[pydantic(rename = "userId", description = "...", ge = 1, le = 100)]`.
+#[derive(Clone, Debug, Default)]
+pub struct FieldAttributes {
+    /// Name of the generated model field.
+    pub rename: Option<String>,
+    /// Leave the field out of the generated model.
+    pub skip: bool,
+    pub description: Option<String>,
+    /// Pydantic `Field(...)` constraints in declaration order.
+    pub constraints: Vec<(String, ConstraintValue)>,
+}
+
+impl FieldAttributes {
+    const KNOWN_ARGS: &'static str = "`rename`, `skip`, `description`, `ge`, `gt`, `le`, `lt`, \
+        `multiple_of`, `min_length`, `max_length`, `pattern`";
+
+    pub fn from_attrs(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut result = Self::default();
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("pydantic")) {
+            attr.parse_args_with(|input: ParseStream| result.parse_args(input))?;
+        }
+        Ok(result)
+    }
+
+    fn add_constraint(&mut self, key: &Ident, value: ConstraintValue) -> syn::Result<()> {
+        let name = key.to_string();
+        if self.constraints.iter().any(|(k, _)| *k == name) {
+            return Err(duplicate_arg(key));
+        }
+        self.constraints.push((name, value));
+        Ok(())
+    }
+
+    /// Like `ContainerAttributes::parse_args`, a constraint repeated in another attribute is a
+    /// duplicate too.
+    fn parse_args(&mut self, input: ParseStream) -> syn::Result<()> {
+        while !input.is_empty() {
+            let key: Ident = input.parse()?;
+            match key.to_string().as_str() {
+                "rename" => {
+                    let value = parse_str_value(input, &key)?;
+                    set_once(&mut self.rename, value.value(), &key)?;
+                }
+                "description" => {
+                    let value = parse_str_value(input, &key)?;
+                    set_once(&mut self.description, value.value(), &key)?;
+                }
+                "skip" => {
+                    if self.skip {
+                        return Err(duplicate_arg(&key));
+                    }
+                    self.skip = true;
+                }
+                "ge" | "gt" | "le" | "lt" | "multiple_of" => {
+                    let value = parse_number_value(input, &key, true)?;
+                    self.add_constraint(&key, ConstraintValue::Number(value))?;
+                }
+                "min_length" | "max_length" => {
+                    let value = parse_number_value(input, &key, false)?;
+                    self.add_constraint(&key, ConstraintValue::Number(value))?;
+                }
+                "pattern" => {
+                    let value = parse_str_value(input, &key)?;
+                    self.add_constraint(&key, ConstraintValue::Str(value.value()))?;
+                }
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        &key,
+                        format!(
+                            "unknown #[pydantic] field argument `{}`, expected one of {}",
+                            key,
+                            Self::KNOWN_ARGS
+                        ),
+                    ))
+                }
+            }
+
+            if input.is_empty() {
+                break;
+            }
+            input.parse::<Token![,]>()?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Parse for FieldAttributes {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut result = Self::default();
+        result.parse_args(input)?;
+        Ok(result)
+    }
+}
+
+/// Parses the `= 10`, `= -1` or `= 0.5` part of a numeric argument.
+fn parse_number_value(input: ParseStream, key: &Ident, allow_float: bool) -> syn::Result<String> {
+    if !input.peek(Token![=]) {
+        return Err(syn::Error::new_spanned(
+            key,
+            format!("expected `{} = <number>`", key),
+        ));
+    }
+    input.parse::<Token![=]>()?;
+    let negative = allow_float && input.parse::<Option<Token![-]>>()?.is_some();
+    let lit: Lit = input.parse()?;
+    let digits = match lit {
+        Lit::Int(ref int) => int.base10_digits().to_owned(),
+        Lit::Float(ref float) if allow_float => float.base10_digits().to_owned(),
+        _ => {
+            let expected = if allow_float {
+                "a number"
+            } else {
+                "an integer"
+            };
+            return Err(syn::Error::new_spanned(
+                lit,
+                format!("expected {} for `{}`", expected, key),
+            ));
+        }
+    };
+
+    Ok(if negative {
+        format!("-{}", digits)
+    } else {
+        digits
+    })
+}
+
+/// Parses the `= "value"` part of a `key = "value"` argument.
+pub(crate) fn parse_str_value(input: ParseStream, key: &Ident) -> syn::Result<LitStr> {
+    if !input.peek(Token![=]) {
+        return Err(syn::Error::new_spanned(
+            key,
+            format!("expected `{} = \"...\"`", key),
+        ));
+    }
+    input.parse::<Token![=]>()?;
+    input.parse()
+}
+
+pub(crate) fn set_once<T>(slot: &mut Option<T>, value: T, key: &Ident) -> syn::Result<()> {
+    if slot.is_some() {
+        return Err(duplicate_arg(key));
+    }
+    *slot = Some(value);
+    Ok(())
+}
+
+fn duplicate_arg(key: &Ident) -> syn::Error {
+    syn::Error::new_spanned(key, format!("duplicate #[pydantic] argument `{}`", key))
+}
@@ -0,0 +1,491 @@
+use std::{
+    collections::BTreeMap,
+    env,
+    path::{Path, PathBuf},
+};
+
+use convert_case::{Case, Casing};
+
+use crate::collector::MetadataCollector;
+use crate::config_file::ConfigFile;
+use crate::error::Error;
+use crate::generators::{
+    generator::PydanticGenerator, EnumCodeGenerator, Layout, PydanticCodeGenerator,
+    PydanticCodeGeneratorFactory, StructCodeGenerator, UnionCodeGenerator,
+};
+use crate::type_mapping::TypeMapping;
+
+/// Built-in generators, each generating one kind of `#[pydantic]` item.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Backend {
+    /// Structs as `BaseModel`, tuple structs as `RootModel`.
+    Structs,
+    /// Unit enums as `enum.Enum`.
+    UnitEnums,
+    /// Enums with data as a discriminated union of variant models.
+    Unions,
+}
+
+impl Backend {
+    pub const ALL: [Backend; 3] = [Backend::Structs, Backend::UnitEnums, Backend::Unions];
+}
+
+/// Naming convention of the generated field names, `#[pydantic(rename = "...")]` takes
+/// precedence.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Naming {
+    /// The Rust field names.
+    #[default]
+    Preserve,
+    /// `created_at` becomes `createdAt`.
+    CamelCase,
+    /// `created_at` becomes `CreatedAt`.
+    PascalCase,
+}
+
+impl Naming {
+    pub(crate) fn apply(&self, name: &str) -> String {
+        match self {
+            Naming::Preserve => name.to_owned(),
+            Naming::CamelCase => name.to_case(Case::Camel),
+            Naming::PascalCase => name.to_case(Case::Pascal),
+        }
+    }
+}
+
+/// How `f32` fields treat values that don't fit an `f32` exactly, they are rounded when
+/// converted by `to_rs()`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum F32Precision {
+    /// Values are accepted as they are.
+    #[default]
+    Ignore,
+    /// Values are accepted with a Python `UserWarning`.
+    Warn,
+    /// Values fail the validation.
+    Validate,
+}
+
+/// What the generation prints as build warnings, the problems found are always printed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+    /// Nothing else.
+    #[default]
+    Quiet,
+    /// A summary once generated.
+    Info,
+    /// Every collected model and written file.
+    Trace,
+}
+
+impl Verbosity {
+    /// Environment variable overriding the verbosity, `quiet`, `info` or `trace`.
+    pub const ENV_VAR: &'static str = "RUSTANTIC_VERBOSITY";
+
+    fn from_env() -> Result<Option<Self>, Error> {
+        let Ok(value) = env::var(Self::ENV_VAR) else {
+            return Ok(None);
+        };
+        match value.trim().to_ascii_lowercase().as_str() {
+            "" => Ok(None),
+            "quiet" => Ok(Some(Verbosity::Quiet)),
+            "info" => Ok(Some(Verbosity::Info)),
+            "trace" => Ok(Some(Verbosity::Trace)),
+            _ => Err(Error::Config(format!(
+                "`{}` must be `quiet`, `info` or `trace`, got '{}'",
+                Self::ENV_VAR,
+                value
+            ))),
+        }
+    }
+}
+
+/// Python version the generated code targets.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PythonVersion {
+    pub major: u8,
+    pub minor: u8,
+}
+
+impl PythonVersion {
+    /// Oldest version supported, builtin generics like `list[int]` need 3.9.
+    pub const MIN: PythonVersion = PythonVersion::new(3, 9);
+
+    pub const fn new(major: u8, minor: u8) -> Self {
+        Self { major, minor }
+    }
+
+    /// Whether `match` statements are available.
+    pub(crate) fn has_match(&self) -> bool {
+        *self >= PythonVersion::new(3, 10)
+    }
+}
+
+impl Default for PythonVersion {
+    fn default() -> Self {
+        PythonVersion::new(3, 10)
+    }
+}
+
+/// Generates the Pydantic models of a pyo3 crate, usually from its `build.rs`.
+///
+/// ```no_run
+/// rustantic::Rustantic::builder()
+///     .package_name("my_package")
+///     .layout(rustantic::Layout::Modules)
+///     .generate()
+///     .unwrap();
+/// ```
+#[derive(Clone, Debug)]
+pub struct Rustantic {
+    pub(crate) crate_dir: PathBuf,
+    pub(crate) output_dir: PathBuf,
+    pub(crate) package_name: String,
+    pub(crate) models_package_name: String,
+    pub(crate) layout: Layout,
+    pub(crate) naming: Naming,
+    pub(crate) python_version: PythonVersion,
+    pub(crate) header: String,
+    pub(crate) backends: Vec<Backend>,
+    pub(crate) generators: Vec<fn() -> Box<dyn PydanticCodeGenerator>>,
+    pub(crate) type_mappings: BTreeMap<String, TypeMapping>,
+    pub(crate) warnings_as_errors: bool,
+    pub(crate) verbosity: Verbosity,
+    pub(crate) log_file: Option<PathBuf>,
+    pub(crate) rerun_if_changed: bool,
+    pub(crate) check: bool,
+    pub(crate) allow_inf_nan: bool,
+    pub(crate) f32_precision: F32Precision,
+    /// Bits of `isize` and `usize` on the target, `CARGO_CFG_TARGET_POINTER_WIDTH` in a build
+    /// script, else 64.
+    pub(crate) pointer_width: u32,
+    /// `rustantic.toml` of the crate, if it has one.
+    pub(crate) config_file: Option<PathBuf>,
+}
+
+impl Rustantic {
+    /// Environment variable enabling the check mode, `1` or `true`, see
+    /// [`RustanticBuilder::check`].
+    pub const CHECK_ENV_VAR: &'static str = "RUSTANTIC_CHECK";
+
+    pub fn builder() -> RustanticBuilder {
+        RustanticBuilder::default()
+    }
+
+    /// Scans the crate sources and writes the models.
+    pub fn generate(&self) -> Result<(), Error> {
+        let mut generator = PydanticGenerator::new(
+            self.clone(),
+            MetadataCollector::new(self.crate_dir.to_string_lossy().as_ref()),
+        );
+        for create in self.generators.iter() {
+            generator = generator.register_with(*create);
+        }
+        for backend in self.backends.iter() {
+            generator = match backend {
+                Backend::Structs => generator.register::<StructCodeGenerator>(),
+                Backend::UnitEnums => generator.register::<EnumCodeGenerator>(),
+                Backend::Unions => generator.register::<UnionCodeGenerator>(),
+            };
+        }
+        generator.generate()
+    }
+
+    pub fn output_dir(&self) -> &Path {
+        &self.output_dir
+    }
+
+    pub fn models_package_name(&self) -> &str {
+        &self.models_package_name
+    }
+}
+
+/// Options of [`Rustantic`], every one has a default suited to a `build.rs`.
+#[derive(Clone, Debug, Default)]
+pub struct RustanticBuilder {
+    crate_dir: Option<PathBuf>,
+    output_dir: Option<PathBuf>,
+    package_name: Option<String>,
+    models_package_name: Option<String>,
+    layout: Layout,
+    naming: Naming,
+    python_version: PythonVersion,
+    header: Option<String>,
+    backends: Option<Vec<Backend>>,
+    generators: Vec<fn() -> Box<dyn PydanticCodeGenerator>>,
+    type_mappings: BTreeMap<String, TypeMapping>,
+    warnings_as_errors: bool,
+    verbosity: Verbosity,
+    log_file: Option<PathBuf>,
+    rerun_if_changed: Option<bool>,
+    check: bool,
+    allow_inf_nan: Option<bool>,
+    f32_precision: F32Precision,
+}
+
+impl RustanticBuilder {
+    /// Root of the crate to scan, its `src/` directory holds the `#[pydantic]` items.
+    /// Defaults to `CARGO_MANIFEST_DIR`.
+    pub fn crate_dir(mut self, path: impl Into<PathBuf>) -> Self {
+        self.crate_dir = Some(path.into());
+        self
+    }
+
+    /// Directory of the models package. Defaults to `<crate_dir>/<package_name>/generated`.
+    pub fn output_dir(mut self, path: impl Into<PathBuf>) -> Self {
+        self.output_dir = Some(path.into());
+        self
+    }
+
+    /// Python module exposing the pyclasses, `#[pydantic(module = "...")]` overrides it per item.
+    /// Defaults to the crate name.
+    pub fn package_name(mut self, name: impl Into<String>) -> Self {
+        self.package_name = Some(name.into());
+        self
+    }
+
+    /// Importable name of the models package. Defaults to `<package_name>.generated`.
+    pub fn models_package_name(mut self, name: impl Into<String>) -> Self {
+        self.models_package_name = Some(name.into());
+        self
+    }
+
+    pub fn layout(mut self, layout: Layout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    pub fn naming(mut self, naming: Naming) -> Self {
+        self.naming = naming;
+        self
+    }
+
+    pub fn python_version(mut self, version: PythonVersion) -> Self {
+        self.python_version = version;
+        self
+    }
+
+    /// Text of the comment starting every generated file, each line is prefixed with `# `.
+    pub fn header(mut self, text: impl Into<String>) -> Self {
+        self.header = Some(text.into());
+        self
+    }
+
+    /// Built-in generators to run, items no generator supports are skipped. Defaults to every
+    /// backend.
+    pub fn backends(mut self, backends: impl IntoIterator<Item = Backend>) -> Self {
+        self.backends = Some(backends.into_iter().collect());
+        self
+    }
+
+    /// Adds a custom generator, registered generators take precedence over the backends in
+    /// their registration order.
+    pub fn register<T: PydanticCodeGeneratorFactory>(mut self) -> Self {
+        self.generators.push(T::create);
+        self
+    }
+
+    /// Generates the Rust type as the Python type of the mapping, taking precedence over the
+    /// built-in mappings and the `[types]` of `rustantic.toml`.
+    ///
+    /// `rust_path` is either a name, matching any type with that name, or a path like
+    /// `rust_decimal::Decimal` or `crate::billing::Money`, matching the type it refers to through
+    /// the `use` declarations.
+    pub fn type_mapping(mut self, rust_path: impl Into<String>, mapping: TypeMapping) -> Self {
+        self.type_mappings.insert(rust_path.into(), mapping);
+        self
+    }
+
+    /// Fails the generation on warnings too, e.g. skipped items or invalid `#[pydantic]` arguments.
+    pub fn warnings_as_errors(mut self, enabled: bool) -> Self {
+        self.warnings_as_errors = enabled;
+        self
+    }
+
+    /// Overridden by the `RUSTANTIC_VERBOSITY` environment variable.
+    pub fn verbosity(mut self, verbosity: Verbosity) -> Self {
+        self.verbosity = verbosity;
+        self
+    }
+
+    /// File the summary of the generation is written to, whatever the verbosity. Defaults to
+    /// `$OUT_DIR/rustantic.log` in a build script.
+    pub fn log_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.log_file = Some(path.into());
+        self
+    }
+
+    /// Prints a `cargo:rerun-if-changed` line for every scanned directory and source file and
+    /// for `rustantic.toml`, so the build script only reruns when they change. Defaults to
+    /// `true` in a build script.
+    pub fn rerun_if_changed(mut self, enabled: bool) -> Self {
+        self.rerun_if_changed = Some(enabled);
+        self
+    }
+
+    /// Compares the generated files with the files on disk instead of writing them, and fails
+    /// with their diff when a file is stale, missing or extra. Overridden by the
+    /// `RUSTANTIC_CHECK` environment variable.
+    pub fn check(mut self, enabled: bool) -> Self {
+        self.check = enabled;
+        self
+    }
+
+    /// Whether float fields accept NaN and infinities, `NotNan` fields never accept NaN.
+    /// Defaults to `true`.
+    pub fn allow_inf_nan(mut self, enabled: bool) -> Self {
+        self.allow_inf_nan = Some(enabled);
+        self
+    }
+
+    pub fn f32_precision(mut self, precision: F32Precision) -> Self {
+        self.f32_precision = precision;
+        self
+    }
+
+    pub fn build(self) -> Result<Rustantic, Error> {
+        let crate_dir = match self.crate_dir {
+            Some(path) => path,
+            None => env::var_os("CARGO_MANIFEST_DIR")
+                .map(PathBuf::from)
+                .ok_or_else(|| {
+                    Error::Config("`crate_dir` is required outside of a build script".to_owned())
+                })?,
+        };
+        let package_name = match self.package_name {
+            Some(name) => name,
+            None => env::var("CARGO_PKG_NAME")
+                .map(|name| name.replace('-', "_"))
+                .map_err(|_| {
+                    Error::Config("`package_name` is required outside of a build script".to_owned())
+                })?,
+        };
+        validate_module_name("package_name", &package_name)?;
+        let models_package_name = self
+            .models_package_name
+            .unwrap_or_else(|| format!("{}.generated", package_name));
+        validate_module_name("models_package_name", &models_package_name)?;
+        let output_dir = self
+            .output_dir
+            .unwrap_or_else(|| crate_dir.join(&package_name).join("generated"));
+        if self.python_version < PythonVersion::MIN {
+            return Err(Error::Config(format!(
+                "Python {}.{} is not supported, the oldest supported version is {}.{}",
+                self.python_version.major,
+                self.python_version.minor,
+                PythonVersion::MIN.major,
+                PythonVersion::MIN.minor
+            )));
+        }
+        let config_file = ConfigFile::load(&crate_dir)?;
+        let mut type_mappings = config_file.types;
+        type_mappings.extend(self.type_mappings);
+        for (rust_path, mapping) in type_mappings.iter() {
+            validate_type_mapping(rust_path, mapping)?;
+        }
+        let verbosity = Verbosity::from_env()?.unwrap_or(self.verbosity);
+        let check = check_from_env()?.unwrap_or(self.check);
+        let log_file = self.log_file.or_else(|| {
+            env::var_os("OUT_DIR").map(|out_dir| PathBuf::from(out_dir).join("rustantic.log"))
+        });
+        let header = self.header.unwrap_or_else(|| {
+            format!(
+                "Generated by rustantic version: {}",
+                env!("CARGO_PKG_VERSION")
+            )
+        });
+
+        Ok(Rustantic {
+            crate_dir,
+            output_dir,
+            package_name,
+            models_package_name,
+            layout: self.layout,
+            naming: self.naming,
+            python_version: self.python_version,
+            header: header
+                .lines()
+                .map(|line| format!("# {}", line).trim_end().to_owned())
+                .collect::<Vec<_>>()
+                .join("\n"),
+            backends: self.backends.unwrap_or_else(|| Backend::ALL.to_vec()),
+            generators: self.generators,
+            type_mappings,
+            warnings_as_errors: self.warnings_as_errors,
+            verbosity,
+            log_file,
+            rerun_if_changed: self
+                .rerun_if_changed
+                .unwrap_or_else(|| env::var_os("OUT_DIR").is_some()),
+            check,
+            allow_inf_nan: self.allow_inf_nan.unwrap_or(true),
+            f32_precision: self.f32_precision,
+            pointer_width: env::var("CARGO_CFG_TARGET_POINTER_WIDTH")
+                .ok()
+                .and_then(|bits| bits.parse().ok())
+                .unwrap_or(64),
+            config_file: config_file.path,
+        })
+    }
+
+    /// Shorthand for `build()` followed by `generate()`.
+    pub fn generate(self) -> Result<(), Error> {
+        self.build()?.generate()
+    }
+}
+
+fn check_from_env() -> Result<Option<bool>, Error> {
+    let Ok(value) = env::var(Rustantic::CHECK_ENV_VAR) else {
+        return Ok(None);
+    };
+    match value.trim().to_ascii_lowercase().as_str() {
+        "" => Ok(None),
+        "1" | "true" => Ok(Some(true)),
+        "0" | "false" => Ok(Some(false)),
+        _ => Err(Error::Config(format!(
+            "`{}` must be `1`, `true`, `0` or `false`, got '{}'",
+            Rustantic::CHECK_ENV_VAR,
+            value
+        ))),
+    }
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Checks `name` is a dotted Python module name.
+fn validate_module_name(option: &str, name: &str) -> Result<(), Error> {
+    if name.split('.').all(is_identifier) {
+        Ok(())
+    } else {
+        Err(Error::Config(format!(
+            "`{}` must be a Python module name, got '{}'",
+            option, name
+        )))
+    }
+}
+
+/// Checks the mapping is keyed by a Rust path and maps to a Python type.
+fn validate_type_mapping(rust_path: &str, mapping: &TypeMapping) -> Result<(), Error> {
+    if !rust_path
+        .split("::")
+        .all(|segment| is_identifier(segment.trim_start_matches("r#")))
+    {
+        return Err(Error::Config(format!(
+            "type mappings must be keyed by a Rust path, got '{}'",
+            rust_path
+        )));
+    }
+    if mapping.python_type.trim().is_empty() {
+        return Err(Error::Config(format!(
+            "the type mapping of '{}' has no Python type",
+            rust_path
+        )));
+    }
+    Ok(())
+}
@@ -1,41 +1,239 @@
-use crate::marcos::has_rustantic_attr;
+use crate::attributes::{ContainerAttributes, FieldAttributes};
+use crate::diagnostic::Diagnostic;
+use crate::error::Error;
+use crate::generators::Layout;
+use crate::marcos::{has_rustantic_attr, is_option_type};
 use crate::models::{
-    ConstructorMetadata, DiscriminatedUnionMetadata, ItemMetadata, StructMetadata,
-    UnionVariantMetadata, UnitEnumMetadata,
+    ArgKind, ConstructorArg, ConstructorMetadata, DiscriminatedUnionMetadata, ItemMetadata,
+    MissingFromRs, StructMetadata, UnionVariantMetadata, UnitEnumMetadata,
+};
+use crate::module_scope::ModuleScopes;
+use crate::signature::{parse_pyo3_signature, SignatureItem};
+use itertools::Itertools;
+use proc_macro2::Span;
+use std::{
+    collections::{hash_map::Entry, BTreeMap, BTreeSet, HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
 };
-use std::{collections::HashMap, fs, path::PathBuf};
 use syn::{
-    parse_file, visit::Visit, FnArg, ImplItem, ImplItemFn, Item, ItemEnum, ItemImpl, ItemStruct,
-    Type,
+    parse_file, parse_quote, visit::Visit, Attribute, Fields, FnArg, GenericArgument, Ident,
+    ImplItem, ImplItemFn, Item, ItemEnum, ItemImpl, ItemMod, ItemStruct, ItemUse, PathArguments,
+    Type, TypePath,
 };
 use walkdir::WalkDir;
 
-pub(crate) struct MetadataCollector {
+/// A `#[new]` function, matched with its entity once every file is scanned.
+struct PendingConstructor {
+    module: String,
+    file: PathBuf,
+    self_ty: Vec<String>,
+    item_fn: ImplItemFn,
+}
+
+/// `#[pydantic]` items of the crate, with the entities they reference.
+pub struct MetadataCollector {
     lib_location: PathBuf,
+    /// Entities keyed by their full Rust path, see `ItemMetadata::path`.
     entities: HashMap<String, ItemMetadata>,
+    /// Entities referenced by the fields of each entity.
+    references: HashMap<String, BTreeSet<String>>,
+    scopes: ModuleScopes,
+    /// Path of the module being visited.
+    module: Vec<String>,
+    /// File being visited, relative to the crate directory.
+    file: PathBuf,
+    constructors: Vec<PendingConstructor>,
+    /// File and line of each entity.
+    locations: HashMap<String, (PathBuf, usize)>,
+    /// Directories and `.rs` files scanned.
+    sources: Vec<PathBuf>,
+    diagnostics: Vec<Diagnostic>,
 }
 
 impl MetadataCollector {
-    pub fn new(lib_location: &str) -> Self {
+    pub(crate) fn new(lib_location: &str) -> Self {
         Self {
             lib_location: PathBuf::from(lib_location),
             entities: HashMap::new(),
+            references: HashMap::new(),
+            scopes: ModuleScopes::default(),
+            module: vec![],
+            file: PathBuf::new(),
+            constructors: vec![],
+            locations: HashMap::new(),
+            sources: vec![],
+            diagnostics: vec![],
         }
     }
 
-    pub fn collect(&mut self) {
-        self.scan_lib();
+    pub(crate) fn collect(&mut self, layout: Layout) -> Result<(), Error> {
+        self.scan_lib()?;
+        self.collect_constructors();
+        self.remove_name_collisions(layout);
+        self.collect_references();
+        Ok(())
+    }
+
+    /// Keeps the entities matching `keep`, references to the others are dropped.
+    pub(crate) fn retain(&mut self, mut keep: impl FnMut(&str, &ItemMetadata) -> bool) {
+        self.entities.retain(|path, meta| keep(path, meta));
+        let entities = &self.entities;
+        self.references
+            .retain(|path, _| entities.contains_key(path));
+        for references in self.references.values_mut() {
+            references.retain(|path| entities.contains_key(path));
+        }
+    }
+
+    /// Problems found while collecting, taken once.
+    pub(crate) fn take_diagnostics(&mut self) -> Vec<Diagnostic> {
+        std::mem::take(&mut self.diagnostics)
+    }
+
+    /// Directories and `.rs` files scanned, changes to them change the generated models.
+    pub(crate) fn sources(&self) -> &[PathBuf] {
+        &self.sources
+    }
+
+    /// File defining the entity, relative to the crate directory, and the line of its name.
+    pub fn location(&self, path: &str) -> Option<(&Path, usize)> {
+        self.locations
+            .get(path)
+            .map(|(file, line)| (file.as_path(), *line))
+    }
+
+    /// Path of the entity `type_path` refers to, in the scope of the entity `owner`.
+    pub fn resolve_type(&self, owner: &str, type_path: &TypePath) -> Option<String> {
+        if type_path.qself.is_some() {
+            return None;
+        }
+        if type_path.path.is_ident("Self") {
+            return Some(owner.to_owned());
+        }
+        self.resolve_type_with(owner, type_path, &|path| self.entities.contains_key(path))
+    }
+
+    /// Path `type_path` refers to in the scope of the entity `owner`, among the paths `is_item`
+    /// accepts. Paths outside the crate are tried as `crate::` paths, 2015 edition style.
+    pub(crate) fn resolve_type_with(
+        &self,
+        owner: &str,
+        type_path: &TypePath,
+        is_item: &dyn Fn(&str) -> bool,
+    ) -> Option<String> {
+        let segments: Vec<String> = type_path
+            .path
+            .segments
+            .iter()
+            .map(|segment| segment.ident.to_string())
+            .collect();
+        let module = self.entities.get(owner)?.module();
+        self.scopes.resolve(module, &segments, is_item)
+    }
+
+    fn resolve_path(&self, module: &str, segments: &[String]) -> Option<String> {
+        self.scopes
+            .resolve(module, segments, &|path| self.entities.contains_key(path))
     }
 
     pub fn entities(&self) -> &HashMap<String, ItemMetadata> {
         &self.entities
     }
 
-    fn scan_lib(&mut self) {
+    /// Whether `to` is part of a reference cycle going back to `from`, including `from == to`.
+    pub fn is_recursive_reference(&self, from: &str, to: &str) -> bool {
+        self.reaches(to, from)
+    }
+
+    /// Why the model of `path` has no `from_rs()`, `None` when it has one. Models referencing a
+    /// model without one don't have one either.
+    pub fn missing_from_rs(&self, path: &str) -> Option<MissingFromRs> {
+        self.missing_from_rs_with(path, &mut HashSet::new())
+    }
+
+    fn missing_from_rs_with(
+        &self,
+        path: &str,
+        visited: &mut HashSet<String>,
+    ) -> Option<MissingFromRs> {
+        // Recursive references are checked once
+        if !visited.insert(path.to_owned()) {
+            return None;
+        }
+        if let Some(ItemMetadata::Struct(meta)) = self.entities.get(path) {
+            if !meta.has_getters() {
+                return Some(MissingFromRs::NoGetters);
+            }
+            let args = meta.unreadable_args();
+            if !args.is_empty() {
+                return Some(MissingFromRs::Unreadable(format!(
+                    "the #[new] arguments {} have no field of the same name to read them from",
+                    quoted_list(args)
+                )));
+            }
+            let fields = meta.fields_without_getter();
+            if !fields.is_empty() {
+                return Some(MissingFromRs::Unreadable(format!(
+                    "the fields {} have no getter, add #[pydantic(get_all)] or #[pyo3(get)]",
+                    quoted_list(fields)
+                )));
+            }
+        }
+        self.references(path)
+            .find(|reference| self.missing_from_rs_with(reference, visited).is_some())
+            .map(|reference| MissingFromRs::Unreadable(format!("'{}' has no from_rs()", reference)))
+    }
+
+    /// Entities referenced by the fields of `ident`.
+    pub fn references(&self, ident: &str) -> impl Iterator<Item = &String> {
+        self.references.get(ident).into_iter().flatten()
+    }
+
+    fn reaches(&self, from: &str, to: &str) -> bool {
+        let mut visited = HashSet::new();
+        let mut pending: Vec<&str> = vec![from];
+        while let Some(ident) = pending.pop() {
+            for reference in self.references(ident) {
+                if reference == to {
+                    return true;
+                }
+                if visited.insert(reference.as_str()) {
+                    pending.push(reference);
+                }
+            }
+        }
+        false
+    }
+
+    fn collect_references(&mut self) {
+        self.references = self
+            .entities
+            .iter()
+            .map(|(path, meta)| {
+                let mut type_paths = vec![];
+                for ty in meta.field_types() {
+                    collect_type_paths(ty, &mut type_paths);
+                }
+                let references = type_paths
+                    .into_iter()
+                    .filter_map(|type_path| self.resolve_type(path, type_path))
+                    .collect();
+                (path.clone(), references)
+            })
+            .collect();
+    }
+
+    fn scan_lib(&mut self) -> Result<(), Error> {
         let src_dir = self.lib_location.join("src");
-        for entry in WalkDir::new(&src_dir) {
-            let entry = entry.expect("Failed to read entry");
-            if entry.file_type().is_file()
+        for entry in WalkDir::new(&src_dir).sort_by_file_name() {
+            let entry = entry.map_err(|err| {
+                let path = err.path().unwrap_or(&src_dir).to_owned();
+                Error::io(path, err.into())
+            })?;
+            if entry.file_type().is_dir() {
+                self.sources.push(entry.path().to_owned());
+            } else if entry.file_type().is_file()
                 && entry
                     .path()
                     .extension()
@@ -43,49 +241,296 @@ impl MetadataCollector {
                     .unwrap_or(false)
             {
                 let path = entry.path();
-                match fs::read_to_string(path) {
-                    Ok(code) => match parse_file(&code) {
-                        Ok(syntax) => {
-                            self.visit_file(&syntax);
-                        }
-                        Err(err) => {
-                            println!("cargo:warning=Failed to parse {}: {}", path.display(), err);
-                        }
-                    },
+                self.sources.push(path.to_owned());
+                let code = fs::read_to_string(path).map_err(|err| Error::io(path, err))?;
+                self.file = path
+                    .strip_prefix(&self.lib_location)
+                    .unwrap_or(path)
+                    .to_owned();
+                match parse_file(&code) {
+                    Ok(syntax) => {
+                        self.module = file_module_path(&src_dir, path);
+                        self.visit_file(&syntax);
+                    }
                     Err(err) => {
-                        println!("cargo:warning=Failed to read {}: {}", path.display(), err);
+                        let diagnostic = Diagnostic::warning(format!(
+                            "failed to parse the file, its items are skipped: {}",
+                            err
+                        ))
+                        .at(self.file.clone(), line(err.span()));
+                        self.diagnostics.push(diagnostic);
                     }
                 }
             }
         }
+        Ok(())
+    }
+
+    fn current_module(&self) -> String {
+        self.module.join("::")
+    }
+
+    /// Reports a problem found in the file being visited.
+    fn warn(&mut self, span: Span, item: impl Into<String>, message: impl Into<String>) {
+        let diagnostic = Diagnostic::warning(message)
+            .at(self.file.clone(), line(span))
+            .item(item);
+        self.diagnostics.push(diagnostic);
+    }
+
+    fn insert_entity(&mut self, meta: ItemMetadata, span: Span) {
+        let path = meta.path();
+        match self.entities.entry(path.clone()) {
+            Entry::Occupied(_) => {
+                self.warn(
+                    span,
+                    path,
+                    "found more than once, only the first one is generated",
+                );
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(meta);
+                self.locations
+                    .insert(path, (self.file.clone(), line(span).unwrap_or_default()));
+            }
+        }
+    }
+
+    /// Models are generated by name, in a single namespace for the flat and single file layouts
+    /// and in the namespace of their module for the modules layout. Items with the same Python
+    /// name in a namespace would overwrite each other, the first one by Rust path is kept. The
+    /// variant models of a union count as names of the union.
+    fn remove_name_collisions(&mut self, layout: Layout) {
+        let mut by_name: BTreeMap<(String, String), Vec<&String>> = BTreeMap::new();
+        for (path, meta) in self.entities.iter() {
+            let module = match layout {
+                Layout::Flat | Layout::SingleFile => String::new(),
+                Layout::Modules => layout.module_name(meta),
+            };
+            for name in meta.model_names() {
+                by_name
+                    .entry((module.clone(), name))
+                    .or_default()
+                    .push(path);
+            }
+        }
+
+        let mut removed = vec![];
+        for ((_, name), mut paths) in by_name.into_iter().filter(|(_, paths)| paths.len() > 1) {
+            paths.sort();
+            let used_by = if self.entities[paths[0]].name() == name {
+                format!("'{}'", paths[0])
+            } else {
+                format!("a variant of '{}'", paths[0])
+            };
+            for path in paths[1..].iter() {
+                let kind = if self.entities[*path].name() == name {
+                    "model name"
+                } else {
+                    "variant model name"
+                };
+                let mut diagnostic = Diagnostic::warning(format!(
+                    "{} '{}' is already used by {}, it isn't generated. \
+                    Use #[pydantic(name = \"...\")] to give it a unique name",
+                    kind, name, used_by
+                ))
+                .item(*path);
+                if let Some((file, line)) = self.locations.get(*path) {
+                    diagnostic = diagnostic.at(file.clone(), Some(*line));
+                }
+                removed.push(diagnostic);
+            }
+        }
+        for diagnostic in removed {
+            self.entities
+                .remove(diagnostic.item.as_deref().unwrap_or_default());
+            self.diagnostics.push(diagnostic);
+        }
     }
 
     fn is_pyo_constructor(&self, fn_: &ImplItemFn) -> bool {
         fn_.attrs.iter().any(|attr| attr.path().is_ident("new"))
     }
 
+    /// Rust path of the item `ident` of the module being visited.
+    fn item_path(&self, ident: &str) -> String {
+        format!("{}::{}", self.current_module(), ident)
+    }
+
+    fn collect_attrs(&mut self, ident: &Ident, attrs: &[Attribute]) -> ContainerAttributes {
+        ContainerAttributes::from_attrs(attrs).unwrap_or_else(|err| {
+            let item = self.item_path(&ident.to_string());
+            self.warn(
+                err.span(),
+                item,
+                format!("invalid #[pydantic] arguments: {}", err),
+            );
+            ContainerAttributes::default()
+        })
+    }
+
+    fn collect_field_attrs(
+        &mut self,
+        ident: &str,
+        fields: &Fields,
+    ) -> HashMap<String, FieldAttributes> {
+        let mut field_attrs = HashMap::new();
+        for (idx, field) in fields.iter().enumerate() {
+            let field_ident = field
+                .ident
+                .as_ref()
+                .map(|i| i.to_string())
+                .unwrap_or_else(|| idx.to_string());
+            match FieldAttributes::from_attrs(&field.attrs) {
+                Ok(attrs) => {
+                    field_attrs.insert(field_ident, attrs);
+                }
+                Err(err) => {
+                    let item = self.item_path(ident);
+                    self.warn(
+                        err.span(),
+                        item,
+                        format!(
+                            "invalid #[pydantic] arguments on field '{}': {}",
+                            field_ident, err
+                        ),
+                    );
+                }
+            }
+        }
+        field_attrs
+    }
+
     fn collect_pydantic_struct(&mut self, item_struct: &ItemStruct) {
-        self.entities.insert(
-            item_struct.ident.to_string(),
+        let attrs = self.collect_attrs(&item_struct.ident, &item_struct.attrs);
+        let field_attrs =
+            self.collect_field_attrs(&item_struct.ident.to_string(), &item_struct.fields);
+        let constructor = if attrs.new {
+            Some(self.synthesize_ctor(&item_struct.fields, &field_attrs))
+        } else {
+            None
+        };
+
+        self.insert_entity(
             ItemMetadata::Struct(StructMetadata {
                 ident: item_struct.ident.to_string(),
-                constructor: None,
+                module: self.current_module(),
+                attrs,
+                constructor,
                 fields: item_struct.fields.clone(),
+                field_attrs,
             }),
+            item_struct.ident.span(),
         );
     }
 
-    fn collect_pydantic_fn(&mut self, item_ident: &str, item_fn: &ImplItemFn) {
-        let args: Vec<(String, Type)> = item_fn
+    /// Mirrors the constructor generated by the macro for `#[pydantic(new)]`.
+    fn synthesize_ctor(
+        &self,
+        fields: &Fields,
+        field_attrs: &HashMap<String, FieldAttributes>,
+    ) -> ConstructorMetadata {
+        let args = fields
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, field)| {
+                let Some(ref ident) = field.ident else {
+                    return Some(ConstructorArg {
+                        name: format!("field_{}", idx),
+                        ty: field.ty.clone(),
+                        kind: ArgKind::Regular,
+                        default: None,
+                    });
+                };
+                let ident = ident.to_string();
+                let skip = field_attrs.get(&ident).map(|a| a.skip).unwrap_or(false);
+                (!skip).then(|| ConstructorArg {
+                    name: ident,
+                    ty: field.ty.clone(),
+                    kind: ArgKind::KeywordOnly,
+                    default: is_option_type(&field.ty).then(|| parse_quote!(None)),
+                })
+            })
+            .collect();
+        ConstructorMetadata { args }
+    }
+
+    fn collect_constructors(&mut self) {
+        for ctor in std::mem::take(&mut self.constructors) {
+            if let Some(path) = self.resolve_path(&ctor.module, &ctor.self_ty) {
+                self.file = ctor.file;
+                self.collect_pydantic_fn(&path, &ctor.module, &ctor.item_fn);
+            }
+        }
+    }
+
+    /// Rewrites the entity references of `ty`, written in `module`, to their full path so
+    /// they resolve from the module of the entity owning the constructor.
+    fn qualify_type(&self, ty: &mut Type, module: &str, owner: &str) {
+        match ty {
+            Type::Path(type_path) => {
+                let segments: Vec<String> = type_path
+                    .path
+                    .segments
+                    .iter()
+                    .map(|segment| segment.ident.to_string())
+                    .collect();
+                let resolved = if segments == ["Self"] {
+                    Some(owner.to_owned())
+                } else {
+                    self.resolve_path(module, &segments)
+                };
+                if let Some(resolved) = resolved.filter(|_| type_path.qself.is_none()) {
+                    let arguments = type_path.path.segments.last().unwrap().arguments.clone();
+                    let mut path: syn::Path =
+                        syn::parse_str(&resolved).expect("Entity paths are valid Rust paths");
+                    path.segments.last_mut().unwrap().arguments = arguments;
+                    type_path.path = path;
+                    return;
+                }
+                for segment in type_path.path.segments.iter_mut() {
+                    if let PathArguments::AngleBracketed(ref mut args) = segment.arguments {
+                        for arg in args.args.iter_mut() {
+                            if let GenericArgument::Type(ref mut inner) = arg {
+                                self.qualify_type(inner, module, owner);
+                            }
+                        }
+                    }
+                }
+            }
+            Type::Tuple(tuple) => tuple
+                .elems
+                .iter_mut()
+                .for_each(|elem| self.qualify_type(elem, module, owner)),
+            Type::Array(array) => self.qualify_type(&mut array.elem, module, owner),
+            Type::Slice(slice) => self.qualify_type(&mut slice.elem, module, owner),
+            Type::Reference(reference) => self.qualify_type(&mut reference.elem, module, owner),
+            Type::Paren(paren) => self.qualify_type(&mut paren.elem, module, owner),
+            Type::Group(group) => self.qualify_type(&mut group.elem, module, owner),
+            _ => {}
+        }
+    }
+
+    fn collect_pydantic_fn(&mut self, item_ident: &str, module: &str, item_fn: &ImplItemFn) {
+        let mut args: Vec<ConstructorArg> = item_fn
             .sig
             .inputs
             .iter()
             .filter_map(|arg| match arg {
                 FnArg::Typed(type_arg) => {
                     if let syn::Pat::Ident(ref arg_ident) = type_arg.pat.as_ref() {
-                        let arg_name = arg_ident.ident.to_string();
-                        let arg_type = type_arg.ty.as_ref().clone();
-                        Some((arg_name, arg_type))
+                        let arg_type = type_arg.ty.as_ref();
+                        if is_python_token(arg_type) {
+                            return None;
+                        }
+                        let mut ty = arg_type.clone();
+                        self.qualify_type(&mut ty, module, item_ident);
+                        Some(ConstructorArg {
+                            name: arg_ident.ident.to_string(),
+                            ty,
+                            kind: ArgKind::Regular,
+                            default: None,
+                        })
                     } else {
                         None
                     }
@@ -94,21 +539,62 @@ impl MetadataCollector {
             })
             .collect();
 
-        if let Some(meta) = self.entities.get_mut(item_ident) {
-            if let ItemMetadata::Struct(struct_meta) = meta {
-                struct_meta.set_ctor(ConstructorMetadata { args });
-            }
+        match parse_pyo3_signature(&item_fn.attrs) {
+            Ok(Some(signature)) => self.apply_signature(&mut args, signature),
+            Ok(None) => {}
+            Err(err) => self.warn(
+                err.span(),
+                item_ident,
+                format!("invalid pyo3 signature on '{}': {}", item_fn.sig.ident, err),
+            ),
+        }
+
+        if let Some(ItemMetadata::Struct(struct_meta)) = self.entities.get_mut(item_ident) {
+            struct_meta.set_ctor(ConstructorMetadata { args });
         }
     }
 
-    fn is_discriminated_union(&self, item_enum: &ItemEnum) -> bool {
-        for variant in item_enum.variants.iter() {
-            if variant.discriminant.is_some() || variant.fields.is_empty() {
-                return false;
+    fn apply_signature(&self, args: &mut [ConstructorArg], signature: Vec<SignatureItem>) {
+        let mut kind = ArgKind::Regular;
+        let mut seen = 0;
+        for item in signature {
+            let (ident, arg_kind, default) = match item {
+                SignatureItem::PositionalOnlyMarker => {
+                    args.iter_mut()
+                        .take(seen)
+                        .for_each(|arg| arg.kind = ArgKind::PositionalOnly);
+                    continue;
+                }
+                SignatureItem::KeywordOnlyMarker => {
+                    kind = ArgKind::KeywordOnly;
+                    continue;
+                }
+                SignatureItem::VarArgs(ident) => {
+                    kind = ArgKind::KeywordOnly;
+                    (ident, ArgKind::VarArgs, None)
+                }
+                SignatureItem::VarKwargs(ident) => (ident, ArgKind::VarKwargs, None),
+                SignatureItem::Arg(ident, default) => (ident, kind, default.map(|expr| *expr)),
+            };
+
+            if let Some(arg) = args.iter_mut().find(|arg| ident == arg.name) {
+                arg.kind = arg_kind;
+                arg.default = default;
+                seen += 1;
             }
         }
+    }
 
-        return true;
+    fn is_discriminated_union(&self, item_enum: &ItemEnum) -> bool {
+        // Same rule as pyo3, an enum is complex as soon as one variant isn't a unit variant
+        item_enum
+            .variants
+            .iter()
+            .all(|variant| variant.discriminant.is_none())
+            && item_enum
+                .variants
+                .iter()
+                .any(|variant| !matches!(variant.fields, Fields::Unit))
     }
 
     fn collect_pydantic_enum(&mut self, item_enum: &ItemEnum) {
@@ -127,12 +613,15 @@ impl MetadataCollector {
                     }
                 })
                 .collect();
-            self.entities.insert(
-                item_enum.ident.to_string(),
+            let attrs = self.collect_attrs(&item_enum.ident, &item_enum.attrs);
+            self.insert_entity(
                 ItemMetadata::UnitEnum(UnitEnumMetadata {
                     ident: item_enum.ident.to_string(),
+                    module: self.current_module(),
+                    attrs,
                     variants,
                 }),
+                item_enum.ident.span(),
             );
         }
     }
@@ -141,25 +630,23 @@ impl MetadataCollector {
         let ident = item_enum.ident.to_string();
         let mut variants = Vec::with_capacity(item_enum.variants.len());
         for variant in item_enum.variants.iter() {
-            match variant.fields {
-                syn::Fields::Unnamed(ref unnamed) => {
-                    variants.push(UnionVariantMetadata {
-                        ident: variant.ident.to_string(),
-                        ty: Some(unnamed.unnamed.first().unwrap().ty.clone()),
-                        named_fields: None,
-                    });
-                }
-                _ => {
-                    println!(
-                        "cargo:error=Rustantic collector don't support variants kinds except unnamed for discriminated unions"
-                    );
-                }
-            };
+            let variant_ident = format!("{}::{}", item_enum.ident, variant.ident);
+            variants.push(UnionVariantMetadata {
+                ident: variant.ident.to_string(),
+                fields: variant.fields.clone(),
+                field_attrs: self.collect_field_attrs(&variant_ident, &variant.fields),
+            });
         }
 
-        self.entities.insert(
-            ident.clone(),
-            ItemMetadata::DiscriminatedUnion(DiscriminatedUnionMetadata { ident, variants }),
+        let attrs = self.collect_attrs(&item_enum.ident, &item_enum.attrs);
+        self.insert_entity(
+            ItemMetadata::DiscriminatedUnion(DiscriminatedUnionMetadata {
+                ident,
+                module: self.current_module(),
+                attrs,
+                variants,
+            }),
+            item_enum.ident.span(),
         );
     }
 }
@@ -167,15 +654,11 @@ impl MetadataCollector {
 impl<'ast> Visit<'ast> for MetadataCollector {
     fn visit_item(&mut self, node: &'ast Item) {
         match node {
-            Item::Struct(ref item_struct) => {
-                if has_rustantic_attr(&item_struct.attrs) {
-                    self.collect_pydantic_struct(item_struct);
-                }
+            Item::Struct(ref item_struct) if has_rustantic_attr(&item_struct.attrs) => {
+                self.collect_pydantic_struct(item_struct);
             }
-            Item::Enum(ref item_enum) => {
-                if has_rustantic_attr(&item_enum.attrs) {
-                    self.collect_pydantic_enum(item_enum);
-                }
+            Item::Enum(ref item_enum) if has_rustantic_attr(&item_enum.attrs) => {
+                self.collect_pydantic_enum(item_enum);
             }
             _ => {}
         }
@@ -183,30 +666,128 @@ impl<'ast> Visit<'ast> for MetadataCollector {
         syn::visit::visit_item(self, node);
     }
 
+    fn visit_item_mod(&mut self, node: &'ast ItemMod) {
+        // Out of line modules are visited with their own file
+        if node.content.is_some() {
+            self.module.push(node.ident.to_string());
+            syn::visit::visit_item_mod(self, node);
+            self.module.pop();
+        }
+    }
+
+    fn visit_item_use(&mut self, node: &'ast ItemUse) {
+        let module = self.current_module();
+        self.scopes.add_use(&module, &node.tree);
+    }
+
     fn visit_item_impl(&mut self, node: &'ast ItemImpl) {
-        let type_name = if let syn::Type::Path(type_path) = &*node.self_ty {
-            type_path
+        if let syn::Type::Path(type_path) = &*node.self_ty {
+            let self_ty: Vec<String> = type_path
                 .path
                 .segments
-                .last()
+                .iter()
                 .map(|seg| seg.ident.to_string())
-                .unwrap_or_else(|| "Unknown".to_string())
-        } else {
-            println!("cargo:error=Rustantic collector didn't find matching struct");
-            "Unknown".to_string()
-        };
-
-        if self.entities.contains_key(&type_name) {
+                .collect();
             for item in &node.items {
                 if let ImplItem::Fn(ref item_fn) = item {
                     if self.is_pyo_constructor(item_fn) {
-                        self.collect_pydantic_fn(&type_name, item_fn);
+                        self.constructors.push(PendingConstructor {
+                            module: self.current_module(),
+                            file: self.file.clone(),
+                            self_ty: self_ty.clone(),
+                            item_fn: item_fn.clone(),
+                        });
                     }
                 }
             }
+        } else {
+            let constructors = node.items.iter().filter_map(|item| match item {
+                ImplItem::Fn(item_fn) if self.is_pyo_constructor(item_fn) => Some(item_fn),
+                _ => None,
+            });
+            for item_fn in constructors.collect_vec() {
+                let diagnostic = Diagnostic::warning(
+                    "#[new] constructor of an impl on an unsupported type is ignored",
+                )
+                .at(self.file.clone(), line(item_fn.sig.ident.span()));
+                self.diagnostics.push(diagnostic);
+            }
         }
 
         // Continue the visit so nested impls (if any) get processed.
         syn::visit::visit_item_impl(self, node);
     }
 }
+
+/// Line of the span, `None` when unknown.
+fn line(span: Span) -> Option<usize> {
+    Some(span.start().line).filter(|line| *line > 0)
+}
+
+/// Module path of a source file, e.g. `src/net/mod.rs` is `crate::net`.
+fn file_module_path(src_dir: &Path, file: &Path) -> Vec<String> {
+    let mut module = vec!["crate".to_owned()];
+    let relative = file
+        .strip_prefix(src_dir)
+        .unwrap_or(file)
+        .with_extension("");
+    let components: Vec<String> = relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy().into_owned())
+        .collect();
+    let is_root = components.len() == 1 && matches!(components[0].as_str(), "lib" | "main");
+    if !is_root {
+        module.extend(
+            components
+                .into_iter()
+                .filter(|component| component != "mod"),
+        );
+    }
+    module
+}
+
+/// pyo3 `Python<'py>` arguments aren't part of the Python signature.
+fn is_python_token(ty: &Type) -> bool {
+    if let Type::Path(type_path) = ty {
+        type_path
+            .path
+            .segments
+            .last()
+            .map(|seg| seg.ident == "Python")
+            .unwrap_or(false)
+    } else {
+        false
+    }
+}
+
+/// Every path in `ty`, including generic arguments.
+fn collect_type_paths<'a>(ty: &'a Type, type_paths: &mut Vec<&'a TypePath>) {
+    match ty {
+        Type::Path(type_path) => {
+            type_paths.push(type_path);
+            for segment in type_path.path.segments.iter() {
+                if let PathArguments::AngleBracketed(ref args) = segment.arguments {
+                    for arg in args.args.iter() {
+                        if let GenericArgument::Type(ref inner) = arg {
+                            collect_type_paths(inner, type_paths);
+                        }
+                    }
+                }
+            }
+        }
+        Type::Tuple(tuple) => tuple
+            .elems
+            .iter()
+            .for_each(|elem| collect_type_paths(elem, type_paths)),
+        Type::Array(array) => collect_type_paths(&array.elem, type_paths),
+        Type::Slice(slice) => collect_type_paths(&slice.elem, type_paths),
+        Type::Reference(reference) => collect_type_paths(&reference.elem, type_paths),
+        Type::Paren(paren) => collect_type_paths(&paren.elem, type_paths),
+        Type::Group(group) => collect_type_paths(&group.elem, type_paths),
+        _ => {}
+    }
+}
+
+fn quoted_list(items: Vec<impl std::fmt::Display>) -> String {
+    items.iter().map(|item| format!("'{}'", item)).join(", ")
+}
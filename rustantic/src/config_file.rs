@@ -0,0 +1,42 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::error::Error;
+use crate::type_mapping::TypeMapping;
+
+/// `rustantic.toml` at the crate root, the builder options take precedence over it.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct ConfigFile {
+    /// Type mappings keyed by Rust path, e.g. `[types."rust_decimal::Decimal"]`.
+    #[serde(default)]
+    pub types: BTreeMap<String, TypeMapping>,
+    /// Where it was read from, `None` for the defaults.
+    #[serde(skip)]
+    pub path: Option<PathBuf>,
+}
+
+impl ConfigFile {
+    pub const FILE_NAME: &'static str = "rustantic.toml";
+
+    /// Reads the config file of the crate, a crate without one gets the defaults.
+    pub fn load(crate_dir: &Path) -> Result<Self, Error> {
+        let path = crate_dir.join(Self::FILE_NAME);
+        if !path.is_file() {
+            return Ok(Self::default());
+        }
+        let text = fs::read_to_string(&path).map_err(|err| Error::io(&path, err))?;
+        let mut config: Self = toml::from_str(&text).map_err(|err| {
+            Error::Config(format!(
+                "{}: {}",
+                path.display(),
+                err.to_string().trim_end()
+            ))
+        })?;
+        config.path = Some(path);
+        Ok(config)
+    }
+}
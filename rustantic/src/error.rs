@@ -0,0 +1,60 @@
+use std::{fmt, io, path::PathBuf};
+
+use crate::diagnostic::Diagnostic;
+
+/// Why the models couldn't be generated.
+#[derive(Debug)]
+pub enum Error {
+    /// Invalid `Rustantic::builder()` option.
+    Config(String),
+    /// Reading the crate sources or writing the generated files failed.
+    Io { path: PathBuf, source: io::Error },
+    /// An item couldn't be generated.
+    Generation(String),
+    /// Problems found in the crate, at least one of them an error or `warnings_as_errors` is
+    /// set. Nothing is written.
+    Diagnostics(Vec<Diagnostic>),
+    /// In check mode, the files on disk differ from the generated ones. Holds their unified
+    /// diff.
+    OutOfDate(String),
+}
+
+impl Error {
+    pub(crate) fn io(path: impl Into<PathBuf>, source: io::Error) -> Self {
+        Error::Io {
+            path: path.into(),
+            source,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Config(message) => write!(f, "invalid rustantic configuration: {}", message),
+            Error::Io { path, source } => write!(f, "{}: {}", path.display(), source),
+            Error::Generation(message) => write!(f, "generation failed: {}", message),
+            Error::Diagnostics(diagnostics) => {
+                write!(f, "generation failed:")?;
+                for diagnostic in diagnostics.iter() {
+                    write!(f, "\n{}: {}", diagnostic.severity, diagnostic)?;
+                }
+                Ok(())
+            }
+            Error::OutOfDate(diff) => write!(
+                f,
+                "the generated models are out of date, regenerate them:\n{}",
+                diff.trim_end()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
@@ -1,11 +1,16 @@
 use crate::{
     collector::MetadataCollector,
+    error::Error,
     models::{ItemMetadata, UnitEnumMetadata},
 };
 
-use super::generator_base::{GeneratorConfig, PydanticCodeGenerator, PydanticCodeGeneratorFactory};
+use std::collections::HashSet;
 
-pub(crate) struct EnumCodeGenerator {}
+use super::generator_base::{
+    GenerationResult, GeneratorConfig, PydanticCodeGenerator, PydanticCodeGeneratorFactory,
+};
+
+pub struct EnumCodeGenerator {}
 
 impl PydanticCodeGeneratorFactory for EnumCodeGenerator {
     fn create() -> Box<dyn PydanticCodeGenerator> {
@@ -23,19 +28,22 @@ impl PydanticCodeGenerator for EnumCodeGenerator {
         config: GeneratorConfig,
         _: &MetadataCollector,
         meta: &ItemMetadata,
-    ) -> Result<String, ()> {
+    ) -> Result<GenerationResult, Error> {
         if let ItemMetadata::UnitEnum(enum_md) = meta {
             Ok(self.generate_code(config, enum_md))
         } else {
-            Err(())
+            Err(Error::Generation(format!(
+                "'{}' is not a unit enum",
+                meta.path()
+            )))
         }
     }
 }
 
 impl EnumCodeGenerator {
-    fn generate_code(&self, config: GeneratorConfig, meta: &UnitEnumMetadata) -> String {
-        let import_code = format!("import enum\nimport {}\n", config.package_name);
-        let mut code = format!("class {}(enum.Enum):\n", &meta.ident);
+    fn generate_code(&self, config: GeneratorConfig, meta: &UnitEnumMetadata) -> GenerationResult {
+        let pyo3_module = config.pyo3_module(&meta.attrs);
+        let mut code = format!("class {}(enum.Enum):\n", meta.name());
         for (var_name, discriminant) in meta.variants.iter() {
             code.push_str(&format!("    {}", &var_name));
             if let Some(disc_val) = discriminant {
@@ -44,21 +52,55 @@ impl EnumCodeGenerator {
                 code.push_str(" = enum.auto()\n");
             }
         }
-        code.push_str("\n");
-        code.push_str(&self.generate_to_pyo3(config.package_name, meta));
+        code.push('\n');
+        let has_match = config.python_version.has_match();
+        code.push_str(&self.generate_to_pyo3(pyo3_module, meta, has_match));
+        code.push('\n');
+        code.push_str(&self.generate_from_pyo3(pyo3_module, meta, has_match));
 
-        format!("{}\n{}\n{}", config.header_comment, import_code, code)
+        GenerationResult {
+            code,
+            additional_imports: HashSet::from([
+                "import enum".to_owned(),
+                format!("import {}", pyo3_module),
+            ]),
+            ..Default::default()
+        }
     }
 
-    fn generate_to_pyo3(&self, package_name: &str, meta: &UnitEnumMetadata) -> String {
+    fn generate_to_pyo3(
+        &self,
+        pyo3_module: &str,
+        meta: &UnitEnumMetadata,
+        has_match: bool,
+    ) -> String {
         let indent = "    ";
         let mut code = format!("{0}def to_rs(self):\n", indent);
+        if !has_match {
+            for (var_name, _) in meta.variants.iter() {
+                code.push_str(&format!(
+                    "{0}{0}if self is {1}.{2}:\n{0}{0}{0}return {3}.{1}.{2}\n",
+                    indent,
+                    meta.name(),
+                    var_name,
+                    pyo3_module
+                ));
+            }
+            code.push_str(&format!(
+                "{0}{0}raise ValueError(f\"Unsupported value '{{self}}'\")\n",
+                indent
+            ));
+            return code;
+        }
         code.push_str(&format!("{0}{0}match self:\n", indent));
 
-        for (ref var_name, _) in meta.variants.iter() {
+        for (var_name, _) in meta.variants.iter() {
             code.push_str(&format!(
                 "{0}{0}{0}case {1}.{2}:\n{0}{0}{0}{0}return {3}.{1}.{2}\n",
-                indent, &meta.ident, var_name, package_name
+                indent,
+                meta.name(),
+                var_name,
+                pyo3_module
             ));
         }
 
@@ -69,4 +111,48 @@ impl EnumCodeGenerator {
 
         code
     }
+
+    fn generate_from_pyo3(
+        &self,
+        pyo3_module: &str,
+        meta: &UnitEnumMetadata,
+        has_match: bool,
+    ) -> String {
+        let indent = "    ";
+        let mut code = format!("{0}@classmethod\n{0}def from_rs(cls, obj):\n", indent);
+        if !has_match {
+            for (var_name, _) in meta.variants.iter() {
+                code.push_str(&format!(
+                    "{0}{0}if obj == {3}.{1}.{2}:\n{0}{0}{0}return cls.{2}\n",
+                    indent,
+                    meta.name(),
+                    var_name,
+                    pyo3_module
+                ));
+            }
+            code.push_str(&format!(
+                "{0}{0}raise ValueError(f\"Unsupported value '{{obj}}'\")\n",
+                indent
+            ));
+            return code;
+        }
+        code.push_str(&format!("{0}{0}match obj:\n", indent));
+
+        for (var_name, _) in meta.variants.iter() {
+            code.push_str(&format!(
+                "{0}{0}{0}case {3}.{1}.{2}:\n{0}{0}{0}{0}return cls.{2}\n",
+                indent,
+                meta.name(),
+                var_name,
+                pyo3_module
+            ));
+        }
+
+        code.push_str(&format!(
+            "{0}{0}{0}case _:\n{0}{0}{0}{0}raise ValueError(f\"Unsupported value '{{obj}}'\")\n",
+            indent
+        ));
+
+        code
+    }
 }
@@ -1,19 +1,26 @@
 use std::collections::{HashMap, HashSet};
 
-use convert_case::{Case, Casing};
 use itertools::Itertools;
-use syn::{GenericArgument, PathArguments, Type, TypePath};
+use syn::{
+    punctuated::Punctuated, Expr, GenericArgument, Lit, PathArguments, PathSegment, Token, Type,
+    TypePath, UnOp,
+};
 
-use crate::models::ItemMetadata;
+use crate::attributes::{ConstraintValue, FieldAttributes};
+use crate::builder::F32Precision;
+use crate::collector::MetadataCollector;
+use crate::models::{ArgKind, ConstructorArg, ItemMetadata};
+use crate::type_mapping::TypeMapping;
 
 use super::generator_base::{GenerationResult, GeneratorConfig};
 
 #[derive(Default)]
 struct FieldGenerationResult {
     additional_imports: HashSet<String>,
+    type_checking_imports: HashSet<String>,
     ty: String,
     default_value: Option<String>,
-    field_properties: HashSet<String>,
+    field_properties: Vec<(String, String)>,
     comment: String,
 }
 
@@ -21,9 +28,10 @@ impl FieldGenerationResult {
     fn new(ty: String, additional_imports: impl Iterator<Item = String>) -> Self {
         Self {
             additional_imports: additional_imports.collect(),
-            ty: ty,
+            type_checking_imports: HashSet::new(),
+            ty,
             default_value: None,
-            field_properties: HashSet::new(),
+            field_properties: Vec::new(),
             comment: "".to_owned(),
         }
     }
@@ -33,7 +41,7 @@ impl FieldGenerationResult {
         any_field.add_any_import();
         any_field.ty = "Any".to_owned();
         if let Some(comment_msg) = comment {
-            any_field.comment.push_str(comment_msg);
+            any_field.push_comment(comment_msg);
         }
 
         any_field
@@ -54,134 +62,360 @@ impl FieldGenerationResult {
             .insert("from pydantic import Field".to_string());
     }
 
-    fn require_field_definition(&self) -> bool {
-        self.default_value.is_some() || !self.field_properties.is_empty()
+    /// Appends to the trailing comment, blank comments are left out so no trailing
+    /// whitespace is emitted.
+    fn push_comment(&mut self, comment: &str) {
+        let comment = comment.trim();
+        if comment.is_empty() {
+            return;
+        }
+        if !self.comment.is_empty() {
+            self.comment.push_str("; ");
+        }
+        self.comment.push_str(comment);
+    }
+
+    /// Sets a `Field(...)` argument, replacing a previous value of the same argument.
+    fn set_field_property(&mut self, key: &str, value: String) {
+        if let Some(prop) = self.field_properties.iter_mut().find(|(k, _)| k == key) {
+            prop.1 = value;
+        } else {
+            self.field_properties.push((key.to_owned(), value));
+        }
     }
 
     fn merge(&mut self, other: FieldGenerationResult) -> String {
         self.additional_imports.extend(other.additional_imports);
-        self.comment.push(' ');
-        self.comment.push_str(&other.comment);
-        self.field_properties.extend(other.field_properties);
+        self.type_checking_imports
+            .extend(other.type_checking_imports);
+        self.push_comment(&other.comment);
+        for (key, value) in other.field_properties {
+            self.set_field_property(&key, value);
+        }
         other.ty
     }
+
+    /// Merges a type nested in a container, its constraints can't be applied to the
+    /// container field so they are attached to the type with `Annotated`.
+    fn merge_nested(&mut self, other: FieldGenerationResult) -> String {
+        if other.field_properties.is_empty() {
+            return self.merge(other);
+        }
+
+        self.additional_imports.extend(other.additional_imports);
+        self.type_checking_imports
+            .extend(other.type_checking_imports);
+        self.additional_imports
+            .insert("from typing import Annotated".to_string());
+        self.add_pydantic_field_import();
+        self.push_comment(&other.comment);
+        format!(
+            "Annotated[{}, Field({})]",
+            other.ty,
+            render_field_properties(other.field_properties)
+        )
+    }
+}
+
+fn render_field_properties(field_properties: Vec<(String, String)>) -> String {
+    field_properties
+        .into_iter()
+        .map(|(key, value)| format!("{}={}", key, value))
+        .join(", ")
 }
 
-pub(crate) struct FieldGenerator<'a> {
+#[derive(Clone, Copy)]
+enum Conversion {
+    ToRs,
+    FromRs,
+}
+
+/// Maps Rust types to Pydantic field annotations and conversions.
+pub struct FieldGenerator<'a> {
     config: GeneratorConfig<'a>,
+    collector: &'a MetadataCollector,
     entities: &'a HashMap<String, ItemMetadata>,
+    /// Path of the entity the fields belong to, types are resolved in its module.
+    owner: &'a str,
 }
 
 impl<'a> FieldGenerator<'a> {
-    pub fn new(config: GeneratorConfig<'a>, entities: &'a HashMap<String, ItemMetadata>) -> Self {
-        Self { config, entities }
+    /// Fields of the entity `owner`, identified by its path, see `ItemMetadata::path`.
+    pub fn new(
+        config: GeneratorConfig<'a>,
+        collector: &'a MetadataCollector,
+        owner: &'a str,
+    ) -> Self {
+        Self {
+            config,
+            collector,
+            entities: collector.entities(),
+            owner,
+        }
     }
 
-    pub fn get_type_ident(&self, ty: &Type) -> Option<String> {
-        if let Type::Path(path_ty) = ty {
-            if path_ty.qself.is_none() {
-                let segment = path_ty.path.segments.last().unwrap();
-                Some(segment.ident.to_string())
-            } else {
-                None
+    /// Python name of the field, see `GeneratorConfig::field_name`.
+    pub fn field_name(&self, ident: &str, attrs: &FieldAttributes) -> String {
+        self.config.field_name(ident, attrs)
+    }
+
+    /// Attribute of the field on the model, see `GeneratorConfig::field_attribute`.
+    pub fn field_attribute(&self, ident: &str, attrs: &FieldAttributes) -> String {
+        self.config.field_attribute(ident, attrs)
+    }
+
+    pub fn generate(
+        &self,
+        field_ident: &str,
+        ty: &Type,
+        attrs: &FieldAttributes,
+    ) -> GenerationResult {
+        self.render_field(field_ident, self.rust_type_to_pydantic(ty), attrs)
+    }
+
+    /// Generates the field of a constructor argument, with the default of the pyo3 `signature`.
+    pub fn generate_arg(&self, arg: &ConstructorArg, attrs: &FieldAttributes) -> GenerationResult {
+        let generated_field = match arg.kind {
+            ArgKind::VarArgs => {
+                let mut result = self.rust_type_to_pydantic(&arg.ty);
+                if result.ty.starts_with("list[") {
+                    result.set_field_property("default_factory", "list".to_owned());
+                } else {
+                    result = FieldGenerationResult::create_any(None);
+                    result.ty = "tuple[Any, ...]".to_owned();
+                    result.default_value = Some("()".to_owned());
+                }
+                result
             }
-        } else {
-            None
-        }
+            ArgKind::VarKwargs => {
+                let mut result = self.rust_type_to_pydantic(option_inner_type(&arg.ty));
+                if !result.ty.starts_with("dict[") {
+                    result = FieldGenerationResult::create_any(None);
+                    result.ty = "dict[str, Any]".to_owned();
+                }
+                result.set_field_property("default_factory", "dict".to_owned());
+                result
+            }
+            _ => {
+                let mut result = self.rust_type_to_pydantic(&arg.ty);
+                match arg
+                    .default
+                    .as_ref()
+                    .map(|expr| (expr, rust_expr_to_python(expr)))
+                {
+                    Some((_, Some(PythonDefault::Value(value)))) => {
+                        result.default_value = Some(value)
+                    }
+                    Some((_, Some(PythonDefault::Factory(factory)))) => {
+                        result.set_field_property("default_factory", factory)
+                    }
+                    Some((expr, None)) => {
+                        result.push_comment(&format!("Rust default: {}", quote::quote!(#expr)))
+                    }
+                    None => {}
+                }
+                result
+            }
+        };
+        self.render_field(&arg.name, generated_field, attrs)
     }
 
-    pub fn generate(&self, field_ident: &str, ty: &Type) -> GenerationResult {
-        let generated_field = self.rust_type_to_pydantic(ty);
-        let mut code = format!("{}: {}", field_ident, generated_field.ty);
-        if generated_field.require_field_definition() {
-            code.push_str(" = Field(");
+    fn render_field(
+        &self,
+        field_ident: &str,
+        mut generated_field: FieldGenerationResult,
+        attrs: &FieldAttributes,
+    ) -> GenerationResult {
+        set_field_attrs(&mut generated_field, attrs);
+
+        let field_name = self.config.field_name(field_ident, attrs);
+        let field_attribute = self.config.field_attribute(field_ident, attrs);
+        if field_attribute != field_name {
+            generated_field.set_field_property("alias", python_str_literal(&field_name));
+        }
+        let mut code = format!("{}: {}", field_attribute, generated_field.ty);
+        if generated_field.field_properties.is_empty() {
             if let Some(default_val) = generated_field.default_value.as_ref() {
-                code.push_str(&format!("default={}", default_val));
-            } else {
-                code.push_str("...");
+                code.push_str(&format!(" = {}", default_val));
             }
-
-            if !generated_field.field_properties.is_empty() {
-                code.push_str(", ");
-                let fields: Vec<String> = generated_field.field_properties.into_iter().collect();
-                code.push_str(&fields.join(", "));
+        } else {
+            generated_field.add_pydantic_field_import();
+            let has_factory = generated_field
+                .field_properties
+                .iter()
+                .any(|(key, _)| key == "default_factory");
+            code.push_str(" = Field(");
+            if let Some(default_val) = generated_field.default_value.as_ref() {
+                code.push_str(&format!("default={}, ", default_val));
+            } else if !has_factory {
+                code.push_str("..., ");
             }
+            code.push_str(&render_field_properties(generated_field.field_properties));
             code.push(')');
         }
 
-        if generated_field.comment.len() > 0 {
+        if !generated_field.comment.is_empty() {
             code.push_str(&format!(" # {}", generated_field.comment));
         }
 
         GenerationResult {
-            code: code,
+            code,
             additional_imports: generated_field.additional_imports,
+            type_checking_imports: generated_field.type_checking_imports,
+            ..Default::default()
         }
     }
 
+    /// Generates the type annotation of `ty` with the field arguments of `attrs`, in an
+    /// `Annotated` if there are any, e.g. for the items of a tuple.
+    pub fn generate_annotated(&self, ty: &Type, attrs: &FieldAttributes) -> GenerationResult {
+        let mut generated_type = self.rust_type_to_pydantic(ty);
+        set_field_attrs(&mut generated_type, attrs);
+        let mut annotated = FieldGenerationResult::default();
+        let code = annotated.merge_nested(generated_type);
+        GenerationResult {
+            code,
+            additional_imports: annotated.additional_imports,
+            type_checking_imports: annotated.type_checking_imports,
+            ..Default::default()
+        }
+    }
+
+    /// Generates only the Python type annotation of `ty`.
+    pub fn generate_type(&self, ty: &Type) -> GenerationResult {
+        let generated_type = self.rust_type_to_pydantic(ty);
+        GenerationResult {
+            code: generated_type.ty,
+            additional_imports: generated_type.additional_imports,
+            type_checking_imports: generated_type.type_checking_imports,
+            ..Default::default()
+        }
+    }
+
+    /// Python expression converting the model value `field_name` to what the pyo3 class expects.
     pub fn generate_to_pyo3(&self, field_name: &str, field_type: &Type) -> String {
-        if let Some(ident_str) = self.get_type_ident(field_type) {
-            if self.entities.contains_key(&ident_str) {
-                return format!("{}.to_rs()", field_name);
-            } else if let Type::Path(type_path) = field_type {
-                if type_path.qself.is_none() {
-                    let segment = type_path.path.segments.last().unwrap();
-                    let ident_str = segment.ident.to_string();
-                    match ident_str.as_str() {
-                        "Option" => {
-                            let inner_ty = self.get_first_inner_type(&segment.arguments);
-                            let inner_ty_to_rs = if let Some(ity) = inner_ty {
-                                self.generate_to_pyo3(field_name, ity)
-                            } else {
-                                field_name.to_owned()
-                            };
-
-                            return format!(
-                                "({} if {} is not None else None)",
-                                inner_ty_to_rs, field_name
-                            );
-                        }
-                        "Vec" => {
-                            let inner_ty = self.get_first_inner_type(&segment.arguments);
-                            let inner_ty_to_rs = if let Some(ity) = inner_ty {
-                                self.generate_to_pyo3("v", ity)
-                            } else {
-                                field_name.to_owned()
-                            };
-
-                            return format!("[{} for v in {}]", inner_ty_to_rs, field_name);
-                        }
-                        "HashSet" | "BTreeSet" => {
-                            let inner_ty = self.get_first_inner_type(&segment.arguments);
-                            let inner_ty_to_rs = if let Some(ity) = inner_ty {
-                                self.generate_to_pyo3("v", ity)
-                            } else {
-                                field_name.to_owned()
-                            };
-
-                            return format!("{{ {} for v in {} }}", inner_ty_to_rs, field_name);
-                        }
-                        "HashMap" | "BTreeMap" | "IndexMap" => {
-                            if let Some((key_ty, val_ty)) =
-                                self.get_map_inner_types(&segment.arguments)
-                            {
-                                let key_to_rs = self.generate_to_pyo3("k", key_ty);
-                                let val_to_rs = self.generate_to_pyo3("v", val_ty);
-                                return format!(
-                                    "{{ {}: {} for v in {}.items() }}",
-                                    key_to_rs, val_to_rs, field_name
-                                );
-                            } else {
-                                return field_name.to_owned();
-                            };
+        self.generate_conversion(field_name, field_type, Conversion::ToRs, 0)
+            .unwrap_or_else(|| field_name.to_owned())
+    }
+
+    /// Python expression converting the pyo3 value `field_name` to the model value.
+    pub fn generate_from_pyo3(&self, field_name: &str, field_type: &Type) -> String {
+        self.generate_conversion(field_name, field_type, Conversion::FromRs, 0)
+            .unwrap_or_else(|| field_name.to_owned())
+    }
+
+    /// Returns `None` when the value can be passed as is. Comprehension variables are
+    /// suffixed with the nesting depth so nested containers don't shadow each other.
+    fn generate_conversion(
+        &self,
+        value: &str,
+        ty: &Type,
+        conversion: Conversion,
+        depth: usize,
+    ) -> Option<String> {
+        let (key_var, item_var) = if depth == 0 {
+            ("k".to_owned(), "v".to_owned())
+        } else {
+            (format!("k{}", depth), format!("v{}", depth))
+        };
+
+        match ty {
+            Type::Paren(paren) => self.generate_conversion(value, &paren.elem, conversion, depth),
+            Type::Group(group) => self.generate_conversion(value, &group.elem, conversion, depth),
+            Type::Tuple(tuple) => {
+                let items: Vec<Option<String>> = tuple
+                    .elems
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, elem)| {
+                        let item = format!("{}[{}]", value, idx);
+                        self.generate_conversion(&item, elem, conversion, depth)
+                    })
+                    .collect();
+                if items.iter().all(Option::is_none) {
+                    return None;
+                }
+                let items = items
+                    .into_iter()
+                    .enumerate()
+                    .map(|(idx, item)| item.unwrap_or_else(|| format!("{}[{}]", value, idx)))
+                    .collect_vec();
+                if items.len() == 1 {
+                    Some(format!("({},)", items[0]))
+                } else {
+                    Some(format!("({})", items.join(", ")))
+                }
+            }
+            Type::Array(array) => {
+                let item =
+                    self.generate_conversion(&item_var, &array.elem, conversion, depth + 1)?;
+                Some(format!("[{} for {} in {}]", item, item_var, value))
+            }
+            Type::Path(type_path) if type_path.qself.is_none() => {
+                let segment = type_path.path.segments.last()?;
+                let ident_str = segment.ident.to_string();
+                if let Some(mapping) = self.type_mapping(type_path) {
+                    let expr = match conversion {
+                        Conversion::ToRs => mapping.to_rs.as_ref(),
+                        Conversion::FromRs => mapping.from_rs.as_ref(),
+                    }?;
+                    return Some(expr.replace("{value}", value));
+                }
+                if let Some(meta) = self
+                    .collector
+                    .resolve_type(self.owner, type_path)
+                    .and_then(|path| self.entities.get(&path))
+                {
+                    return Some(match conversion {
+                        Conversion::ToRs => format!("{}.to_rs()", value),
+                        Conversion::FromRs => format!("{}.from_rs({})", meta.name(), value),
+                    });
+                }
+
+                match ident_str.as_str() {
+                    "Box" | "Rc" | "Arc" => {
+                        let inner_ty = self.get_first_inner_type(&segment.arguments)?;
+                        self.generate_conversion(value, inner_ty, conversion, depth)
+                    }
+                    "Option" => {
+                        let inner_ty = self.get_first_inner_type(&segment.arguments)?;
+                        let inner = self.generate_conversion(value, inner_ty, conversion, depth)?;
+                        Some(format!("({} if {} is not None else None)", inner, value))
+                    }
+                    "Vec" | "VecDeque" => {
+                        let inner_ty = self.get_first_inner_type(&segment.arguments)?;
+                        let item =
+                            self.generate_conversion(&item_var, inner_ty, conversion, depth + 1)?;
+                        Some(format!("[{} for {} in {}]", item, item_var, value))
+                    }
+                    "HashSet" | "BTreeSet" => {
+                        let inner_ty = self.get_first_inner_type(&segment.arguments)?;
+                        let item =
+                            self.generate_conversion(&item_var, inner_ty, conversion, depth + 1)?;
+                        Some(format!("{{{} for {} in {}}}", item, item_var, value))
+                    }
+                    "HashMap" | "BTreeMap" | "IndexMap" => {
+                        let (key_ty, val_ty) = self.get_map_inner_types(&segment.arguments)?;
+                        let key = self.generate_conversion(&key_var, key_ty, conversion, depth + 1);
+                        let val =
+                            self.generate_conversion(&item_var, val_ty, conversion, depth + 1);
+                        if key.is_none() && val.is_none() {
+                            return None;
                         }
-                        _ => {}
+                        Some(format!(
+                            "{{{}: {} for {}, {} in {}.items()}}",
+                            key.unwrap_or_else(|| key_var.clone()),
+                            val.unwrap_or_else(|| item_var.clone()),
+                            key_var,
+                            item_var,
+                            value
+                        ))
                     }
+                    _ => None,
                 }
             }
+            _ => None,
         }
-
-        field_name.to_owned()
     }
 
     fn rust_type_to_pydantic(&self, ty: &Type) -> FieldGenerationResult {
@@ -198,21 +432,57 @@ impl<'a> FieldGenerator<'a> {
                         result.ty = "str".to_string();
                     } else {
                         result.add_any_import();
-                        result.comment.push_str(&format!("Reference to {last_seg}"));
+                        result.push_comment(&format!("Reference to {last_seg}"));
                         result.ty = "Any".to_owned();
                     }
                 } else {
                     result.add_any_import();
-                    result.comment.push_str("Unknown ref type");
+                    result.push_comment("Unknown ref type");
                     result.ty = "Any".to_owned();
                 };
                 result
             }
-            // Catch-all for function pointers, never types, tuples, etc.
+            Type::Tuple(tuple_type) => {
+                let mut result = FieldGenerationResult::default();
+                if tuple_type.elems.is_empty() {
+                    result.ty = "None".to_owned();
+                } else {
+                    let elems = tuple_type
+                        .elems
+                        .iter()
+                        .map(|t| {
+                            let ty_res = self.rust_type_to_pydantic(t);
+                            result.merge_nested(ty_res)
+                        })
+                        .join(", ");
+                    result.ty = format!("tuple[{}]", elems);
+                }
+                result
+            }
+            // Fixed size arrays are lists of exactly `N` items
+            Type::Array(array_type) => {
+                let mut result = FieldGenerationResult::default();
+                result.ty = if is_byte(&array_type.elem) {
+                    "bytes".to_owned()
+                } else {
+                    let elem = self.rust_type_to_pydantic(&array_type.elem);
+                    format!("list[{}]", result.merge_nested(elem))
+                };
+                if let Expr::Lit(ref len) = array_type.len {
+                    if let Lit::Int(ref len) = len.lit {
+                        result.set_field_property("min_length", len.base10_digits().to_owned());
+                        result.set_field_property("max_length", len.base10_digits().to_owned());
+                    }
+                }
+                result
+            }
+            Type::Paren(paren_type) => self.rust_type_to_pydantic(&paren_type.elem),
+            Type::Group(group_type) => self.rust_type_to_pydantic(&group_type.elem),
+            // Catch-all for function pointers, never types, etc.
             _ => {
                 let mut result = FieldGenerationResult::default();
                 result.add_any_import();
-                result.comment.push_str("Unknown ref type");
+                result.push_comment("Unknown ref type");
                 result.ty = "Any".to_owned();
                 result
             }
@@ -224,21 +494,34 @@ impl<'a> FieldGenerator<'a> {
             FieldGenerationResult::create_any(Some(
                 "Qualified (e.g. <T as Trait>::Type). We'll just fallback",
             ))
+        } else if let Some(mapping) = self.type_mapping(type_path) {
+            let mut result = FieldGenerationResult::new(
+                mapping.python_type.clone(),
+                mapping.imports.iter().cloned(),
+            );
+            if !mapping.constraints.is_empty() {
+                result.add_pydantic_field_import();
+            }
+            for (key, value) in mapping.constraints.iter() {
+                result.set_field_property(key, constraint_to_python(value));
+            }
+            result
+        } else if let Some(path) = self.collector.resolve_type(self.owner, type_path) {
+            self.custom_type_to_pydantic(&path)
         } else {
             let mut result = FieldGenerationResult::default();
 
             // Get the last segment of the path, e.g. "Vec" for "std::vec::Vec"
             let segment = type_path.path.segments.last().unwrap();
             let ident_str = segment.ident.to_string();
+            if let Some(number) = self
+                .integer_to_pydantic(segment)
+                .or_else(|| self.float_to_pydantic(segment))
+            {
+                return number;
+            }
 
             result.ty = match ident_str.as_str() {
-                "i8" | "i16" | "i32" | "i64" => "int".to_string(),
-                "u8" | "u16" | "u32" | "u64" | "isize" | "usize" => {
-                    result.add_pydantic_field_import();
-                    result.field_properties.insert("ge=0".to_owned());
-                    "int".to_string()
-                }
-                "f32" | "f64" => "float".to_string(),
                 "bool" => "bool".to_string(),
                 "String" => "str".to_string(),
                 "Option" => {
@@ -246,7 +529,22 @@ impl<'a> FieldGenerator<'a> {
                     option.add_optional_import();
                     return option;
                 }
-                "Vec" => {
+                "Box" | "Rc" | "Arc" => {
+                    return match self.get_first_inner_type(&segment.arguments) {
+                        Some(inner_ty) => self.rust_type_to_pydantic(inner_ty),
+                        None => FieldGenerationResult::create_any(None),
+                    };
+                }
+                // pyo3 converts them from and to `bytes`
+                "Vec"
+                    if self
+                        .get_first_inner_type(&segment.arguments)
+                        .is_some_and(is_byte) =>
+                {
+                    "bytes".to_string()
+                }
+                "Bytes" => "bytes".to_string(),
+                "Vec" | "VecDeque" => {
                     return self.resolve_inner_type("list", &segment.arguments);
                 }
                 "Uuid" => {
@@ -291,10 +589,9 @@ impl<'a> FieldGenerator<'a> {
                         .insert("import pathlib".to_owned());
                     "pathlib.Path".to_string()
                 }
-                // Custom or unknown type
+                // Unknown type
                 _ => {
-                    // Custom pydantic ref
-                    return self.custom_type_to_pydantic(&ident_str);
+                    return FieldGenerationResult::create_any(Some("Unknown ref type"));
                 }
             };
 
@@ -302,6 +599,132 @@ impl<'a> FieldGenerator<'a> {
         }
     }
 
+    /// Integers get the bounds of their width, `NonZeroU32` and `NonZero<u32>` also exclude 0.
+    fn integer_to_pydantic(&self, segment: &'a PathSegment) -> Option<FieldGenerationResult> {
+        let ident_str = segment.ident.to_string();
+        let (name, non_zero) = match ident_str.strip_prefix("NonZero") {
+            Some("") => match self.get_first_inner_type(&segment.arguments)? {
+                Type::Path(inner) if inner.qself.is_none() => {
+                    (inner.path.segments.last()?.ident.to_string(), true)
+                }
+                _ => return None,
+            },
+            Some(suffix) => (suffix.to_lowercase(), true),
+            None => (ident_str, false),
+        };
+        let (min, max) = integer_bounds(&name, self.config.pointer_width)?;
+
+        let mut result = FieldGenerationResult::new("int".to_owned(), std::iter::empty());
+        result.add_pydantic_field_import();
+        if non_zero && min == "0" {
+            result.set_field_property("gt", min);
+        } else {
+            result.set_field_property("ge", min);
+            if non_zero {
+                result
+                    .additional_imports
+                    .insert("from annotated_types import Predicate".to_owned());
+                result
+                    .additional_imports
+                    .insert("from typing import Annotated".to_owned());
+                result.ty = "Annotated[int, Predicate(lambda v: v != 0)]".to_owned();
+            }
+        }
+        result.set_field_property("le", max);
+        Some(result)
+    }
+
+    /// `f32`, `f64` and their `OrderedFloat` and `NotNan` wrappers, following the float options.
+    fn float_to_pydantic(&self, segment: &'a PathSegment) -> Option<FieldGenerationResult> {
+        let ident_str = segment.ident.to_string();
+        let (name, not_nan) = match ident_str.as_str() {
+            "OrderedFloat" | "NotNan" => match self.get_first_inner_type(&segment.arguments)? {
+                Type::Path(inner) if inner.qself.is_none() => (
+                    inner.path.segments.last()?.ident.to_string(),
+                    ident_str == "NotNan",
+                ),
+                _ => return None,
+            },
+            _ => (ident_str, false),
+        };
+        let is_f32 = match name.as_str() {
+            "f32" => true,
+            "f64" => false,
+            _ => return None,
+        };
+
+        let mut result = FieldGenerationResult::new("float".to_owned(), std::iter::empty());
+        let mut metadata = vec![];
+        if not_nan && self.config.allow_inf_nan {
+            result.additional_imports.insert("import math".to_owned());
+            result
+                .additional_imports
+                .insert("from annotated_types import Predicate".to_owned());
+            metadata.push("Predicate(lambda v: not math.isnan(v))".to_owned());
+        }
+        if is_f32 && self.config.f32_precision != F32Precision::Ignore {
+            // NaN is the only value not equal to itself once converted
+            let exact = "math.isnan(v) or array.array(\"f\", [v])[0] == v";
+            result.additional_imports.insert("import array".to_owned());
+            result.additional_imports.insert("import math".to_owned());
+            if self.config.f32_precision == F32Precision::Warn {
+                result
+                    .additional_imports
+                    .insert("import warnings".to_owned());
+                result
+                    .additional_imports
+                    .insert("from pydantic import AfterValidator".to_owned());
+                let warn = "warnings.warn(f\"{v} is not exactly representable as f32\")";
+                metadata.push(format!(
+                    "AfterValidator(lambda v: v if {} else {} or v)",
+                    exact, warn
+                ));
+            } else {
+                result
+                    .additional_imports
+                    .insert("from annotated_types import Predicate".to_owned());
+                metadata.push(format!("Predicate(lambda v: {})", exact));
+            }
+        }
+
+        if !self.config.allow_inf_nan {
+            result.add_pydantic_field_import();
+            if metadata.is_empty() {
+                result.set_field_property("allow_inf_nan", "False".to_owned());
+            } else {
+                // Pydantic applies it to plain floats only, so before the validators
+                metadata.insert(0, "Field(allow_inf_nan=False)".to_owned());
+            }
+        }
+        if !metadata.is_empty() {
+            result
+                .additional_imports
+                .insert("from typing import Annotated".to_owned());
+            result.ty = format!("Annotated[float, {}]", metadata.join(", "));
+        }
+        Some(result)
+    }
+
+    /// Mapping of the type, by the path it refers to in the module of the owner, else by name.
+    fn type_mapping(&self, type_path: &TypePath) -> Option<&'a TypeMapping> {
+        let mappings = self.config.type_mappings;
+        if mappings.is_empty() {
+            return None;
+        }
+        let lookup = |path: &str| {
+            mappings
+                .get(path)
+                .or_else(|| mappings.get(path.strip_prefix("crate::")?))
+        };
+        self.collector
+            .resolve_type_with(self.owner, type_path, &|path| lookup(path).is_some())
+            .and_then(|path| lookup(&path))
+            .or_else(|| {
+                let segment = type_path.path.segments.last()?;
+                mappings.get(&segment.ident.to_string())
+            })
+    }
+
     fn get_inner_types(&self, inner_type: &'a PathArguments) -> Option<Vec<&'a Type>> {
         if let PathArguments::AngleBracketed(angle_args) = inner_type {
             Some(
@@ -353,7 +776,12 @@ impl<'a> FieldGenerator<'a> {
                 .into_iter()
                 .map(|t| {
                     let ty_res = self.rust_type_to_pydantic(t);
-                    result.merge(ty_res)
+                    // `Optional` is transparent for constraints
+                    if parent_type == "Optional" {
+                        result.merge(ty_res)
+                    } else {
+                        result.merge_nested(ty_res)
+                    }
                 })
                 .join(", ");
             result.ty = format!("{}[{}]", parent_type, generic_args);
@@ -365,18 +793,30 @@ impl<'a> FieldGenerator<'a> {
         }
     }
 
-    fn custom_type_to_pydantic(&self, ident: &str) -> FieldGenerationResult {
-        if self.entities.contains_key(ident) {
-            FieldGenerationResult::new(
-                format!("{}", &ident),
-                [format!(
-                    "from {0}.{1} import {2}",
-                    self.config.models_package_name,
-                    &ident.to_case(Case::Snake),
-                    &ident
+    /// Reference to the model of the entity at `path`.
+    fn custom_type_to_pydantic(&self, path: &str) -> FieldGenerationResult {
+        if let Some(meta) = self.entities.get(path) {
+            let name = meta.name();
+            let layout = self.config.layout;
+            let imports: Vec<String> = if layout.module(path) == layout.module(self.owner) {
+                vec![]
+            } else {
+                vec![format!(
+                    "from {} import {}",
+                    layout.qualified_module(path),
+                    name
                 )]
-                .into_iter(),
-            )
+            };
+            // Deferred references are forward references resolved by the package `__init__.py`,
+            // importing them at runtime would be circular
+            if layout.is_deferred(self.collector, self.owner, path) {
+                let mut result =
+                    FieldGenerationResult::new(format!("\"{}\"", name), [].into_iter());
+                result.type_checking_imports.extend(imports);
+                result
+            } else {
+                FieldGenerationResult::new(name.to_owned(), imports.into_iter())
+            }
         }
         // Unknown type
         else {
@@ -384,3 +824,189 @@ impl<'a> FieldGenerator<'a> {
         }
     }
 }
+
+/// Sets the `description` and the constraints of the field arguments.
+fn set_field_attrs(generated_field: &mut FieldGenerationResult, attrs: &FieldAttributes) {
+    if let Some(ref description) = attrs.description {
+        generated_field.set_field_property("description", python_str_literal(description));
+    }
+    for (key, value) in attrs.constraints.iter() {
+        generated_field.set_field_property(key, constraint_to_python(value));
+    }
+}
+
+/// Renders a field constraint as a `Field(...)` argument value, strings are quoted.
+fn constraint_to_python(value: &ConstraintValue) -> String {
+    match value {
+        ConstraintValue::Number(number) => number.clone(),
+        ConstraintValue::Str(string) => python_str_literal(string),
+    }
+}
+
+/// Renders `value` as a double quoted Python string literal.
+fn python_str_literal(value: &str) -> String {
+    let mut literal = String::with_capacity(value.len() + 2);
+    literal.push('"');
+    for ch in value.chars() {
+        match ch {
+            '\\' => literal.push_str("\\\\"),
+            '"' => literal.push_str("\\\""),
+            '\n' => literal.push_str("\\n"),
+            '\r' => literal.push_str("\\r"),
+            '\t' => literal.push_str("\\t"),
+            _ => literal.push(ch),
+        }
+    }
+    literal.push('"');
+    literal
+}
+
+/// Python default of a field, `Factory` is used for mutable values.
+enum PythonDefault {
+    Value(String),
+    Factory(String),
+}
+
+/// Translates the Rust expression of a pyo3 `signature` default, `None` when it has no
+/// obvious Python equivalent.
+fn rust_expr_to_python(expr: &Expr) -> Option<PythonDefault> {
+    match expr {
+        Expr::Lit(expr_lit) => {
+            let value = match expr_lit.lit {
+                Lit::Int(ref int) => int.base10_digits().to_owned(),
+                Lit::Float(ref float) => float.base10_digits().to_owned(),
+                Lit::Bool(ref b) => if b.value { "True" } else { "False" }.to_owned(),
+                Lit::Str(ref string) => python_str_literal(&string.value()),
+                Lit::Char(ref ch) => python_str_literal(&ch.value().to_string()),
+                _ => return None,
+            };
+            Some(PythonDefault::Value(value))
+        }
+        Expr::Unary(unary) if matches!(unary.op, UnOp::Neg(_)) => {
+            match rust_expr_to_python(&unary.expr)? {
+                PythonDefault::Value(value) => Some(PythonDefault::Value(format!("-{}", value))),
+                PythonDefault::Factory(_) => None,
+            }
+        }
+        Expr::Path(expr_path) if expr_path.path.is_ident("None") => {
+            Some(PythonDefault::Value("None".to_owned()))
+        }
+        Expr::Paren(paren) => rust_expr_to_python(&paren.expr),
+        Expr::Group(group) => rust_expr_to_python(&group.expr),
+        Expr::Call(call) => {
+            let Expr::Path(ref func) = *call.func else {
+                return None;
+            };
+            let segments: Vec<String> = func
+                .path
+                .segments
+                .iter()
+                .map(|seg| seg.ident.to_string())
+                .collect();
+            let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+            match (segments.as_slice(), call.args.len()) {
+                ([.., "Some"], 1) | ([.., "String", "from"], 1) => {
+                    rust_expr_to_python(&call.args[0])
+                }
+                ([.., "String", "new"], 0) => Some(PythonDefault::Value("\"\"".to_owned())),
+                ([.., "Vec", "new"], 0) => Some(PythonDefault::Factory("list".to_owned())),
+                ([.., "HashMap" | "BTreeMap" | "IndexMap", "new"], 0) => {
+                    Some(PythonDefault::Factory("dict".to_owned()))
+                }
+                ([.., "HashSet" | "BTreeSet", "new"], 0) => {
+                    Some(PythonDefault::Factory("set".to_owned()))
+                }
+                _ => None,
+            }
+        }
+        Expr::MethodCall(call)
+            if call.args.is_empty()
+                && matches!(
+                    call.method.to_string().as_str(),
+                    "to_string" | "to_owned" | "into"
+                ) =>
+        {
+            match *call.receiver {
+                Expr::Lit(_) => rust_expr_to_python(&call.receiver),
+                _ => None,
+            }
+        }
+        Expr::Macro(expr_macro) if expr_macro.mac.path.is_ident("vec") => {
+            let items = expr_macro
+                .mac
+                .parse_body_with(Punctuated::<Expr, Token![,]>::parse_terminated)
+                .ok()?;
+            if items.is_empty() {
+                return Some(PythonDefault::Factory("list".to_owned()));
+            }
+            let items = items
+                .iter()
+                .map(|item| match rust_expr_to_python(item)? {
+                    PythonDefault::Value(value) => Some(value),
+                    PythonDefault::Factory(_) => None,
+                })
+                .collect::<Option<Vec<String>>>()?;
+            Some(PythonDefault::Factory(format!(
+                "lambda: [{}]",
+                items.join(", ")
+            )))
+        }
+        _ => None,
+    }
+}
+
+/// `T` for `Option<T>`, `ty` otherwise.
+pub(crate) fn option_inner_type(ty: &Type) -> &Type {
+    if let Type::Path(type_path) = ty {
+        if let Some(segment) = type_path.path.segments.last() {
+            if segment.ident == "Option" {
+                if let PathArguments::AngleBracketed(ref args) = segment.arguments {
+                    if let Some(GenericArgument::Type(inner)) = args.args.first() {
+                        return inner;
+                    }
+                }
+            }
+        }
+    }
+    ty
+}
+
+fn is_byte(ty: &Type) -> bool {
+    match ty {
+        Type::Path(type_path) if type_path.qself.is_none() => type_path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "u8" && segment.arguments.is_none()),
+        _ => false,
+    }
+}
+
+/// Smallest and largest value of a Rust integer type, `isize` and `usize` have the pointer width
+/// of the target.
+fn integer_bounds(name: &str, pointer_width: u32) -> Option<(String, String)> {
+    let (signed, bits) = match name {
+        "isize" => (true, pointer_width),
+        "usize" => (false, pointer_width),
+        "i8" => (true, 8),
+        "i16" => (true, 16),
+        "i32" => (true, 32),
+        "i64" => (true, 64),
+        "i128" => (true, 128),
+        "u8" => (false, 8),
+        "u16" => (false, 16),
+        "u32" => (false, 32),
+        "u64" => (false, 64),
+        "u128" => (false, 128),
+        _ => return None,
+    };
+    let shift = 128 - bits;
+    Some(if signed {
+        (
+            (i128::MIN >> shift).to_string(),
+            (i128::MAX >> shift).to_string(),
+        )
+    } else {
+        ("0".to_owned(), (u128::MAX >> shift).to_string())
+    })
+}
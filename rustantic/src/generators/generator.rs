@@ -1,123 +1,653 @@
-use std::path::PathBuf;
-use std::{env, fs};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
+use std::{fs, io};
 
+use crate::builder::{Rustantic, Verbosity};
 use crate::collector::MetadataCollector;
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::error::Error;
 use crate::generators::generator_base::PydanticCodeGenerator;
 use crate::models::ItemMetadata;
-use convert_case::{Case, Casing};
 use itertools::Itertools;
+use similar::TextDiff;
 
-use super::generator_base::{GeneratorConfig, PydanticCodeGeneratorFactory};
+use super::generator_base::{
+    render_imports, GenerationResult, GeneratorConfig, PydanticCodeGeneratorFactory,
+};
+use super::layout::{child_of, Layout, PackageLayout};
+use super::log::GenerationLog;
 
 pub struct PydanticGenerator {
-    header_comment: String,
-    package_name: String,
-    base_path: String,
-    models_package_name: String,
+    settings: Rustantic,
     collector: MetadataCollector,
     generators: Vec<Box<dyn PydanticCodeGenerator>>,
 }
 
 impl PydanticGenerator {
-    pub(crate) fn new(
-        package_name: String,
-        base_path: String,
-        models_package_name: String,
-        collector: MetadataCollector,
-    ) -> Self {
-        let version = env::var("CARGO_PKG_VERSION").unwrap();
-        let header_comment = format!("# Generated by rustantic version: {}", version).to_string();
+    pub(crate) fn new(settings: Rustantic, collector: MetadataCollector) -> Self {
         Self {
-            header_comment,
-            package_name,
-            base_path,
-            models_package_name,
+            settings,
             collector,
             generators: Vec::new(),
         }
     }
 
-    pub(crate) fn register<T>(mut self) -> Self
+    pub(crate) fn register<T>(self) -> Self
     where
         T: PydanticCodeGeneratorFactory,
     {
-        self.generators.push(T::create());
-        self
+        self.register_with(T::create)
     }
 
-    pub(crate) fn generate(&mut self) {
-        println!(
-            "cargo::warning=Rustantic generator stated, packageName='{}', basePath'{}', modelPackage='{}'",
-            &self.package_name, &self.base_path, &self.models_package_name
-        );
+    pub(crate) fn register_with(mut self, create: fn() -> Box<dyn PydanticCodeGenerator>) -> Self {
+        self.generators.push(create());
+        self
+    }
 
-        self.collector.collect();
+    /// Generates the models, the log file is written whether it succeeds or not.
+    pub(crate) fn generate(&mut self) -> Result<(), Error> {
+        let mut log = GenerationLog::new(self.settings.verbosity);
+        log.trace(format!(
+            "generating '{}' from {} into {}",
+            self.settings.models_package_name,
+            self.settings.crate_dir.display(),
+            self.settings.output_dir.display()
+        ));
+        let result = self.run(&mut log);
+        if let Err(ref err) = result {
+            log.trace(err.to_string());
+        }
+        match self.settings.log_file {
+            Some(ref log_file) => result.and(log.save(log_file)),
+            None => result,
+        }
+    }
 
-        println!(
-            "cargo:warning=Rustantic collected '{}' items",
-            self.collector.entities().len()
-        );
-        self.generate_init_file();
-        self.collector
+    fn run(&mut self, log: &mut GenerationLog) -> Result<(), Error> {
+        self.collector.collect(self.settings.layout)?;
+        if self.settings.rerun_if_changed {
+            for path in self
+                .collector
+                .sources()
+                .iter()
+                .chain(self.settings.config_file.iter())
+            {
+                println!("cargo:rerun-if-changed={}", path.display());
+            }
+            println!("cargo:rerun-if-env-changed={}", Verbosity::ENV_VAR);
+            println!("cargo:rerun-if-env-changed={}", Rustantic::CHECK_ENV_VAR);
+        }
+        let mut diagnostics = self.collector.take_diagnostics();
+        let unsupported: Vec<String> = self
+            .collector
             .entities()
             .iter()
-            .for_each(|(_, meta)| self.generate_entity_file(meta));
+            .filter(|(_, meta)| !self.is_supported(meta))
+            .map(|(path, _)| path.clone())
+            .sorted()
+            .collect();
+        for path in unsupported.iter() {
+            diagnostics.push(self.locate(
+                Diagnostic::warning("skipped, no registered generator supports it"),
+                path,
+            ));
+        }
+        self.collector
+            .retain(|path, _| !unsupported.iter().any(|skipped| skipped == path));
+
+        for path in self.collector.entities().keys().sorted() {
+            log.trace(format!("collected '{}'", path));
+        }
+        let layout = PackageLayout::new(
+            self.settings.layout,
+            &self.settings.models_package_name,
+            &self.collector,
+        );
+        let mut generated = HashMap::new();
+        for path in self.collector.entities().keys().sorted() {
+            match self.generate_entity(&layout, path) {
+                Ok(result) => {
+                    for warning in result.warnings.iter() {
+                        diagnostics.push(self.locate(Diagnostic::warning(warning.clone()), path));
+                    }
+                    generated.insert(path.as_str(), result);
+                }
+                Err(err) => {
+                    let message = match err {
+                        Error::Generation(message) => message,
+                        err => err.to_string(),
+                    };
+                    diagnostics.push(self.locate(Diagnostic::error(message), path));
+                }
+            }
+        }
+        let modules: Vec<(&str, String)> = layout
+            .all_modules()
+            .into_iter()
+            .map(|module| {
+                let code = self.generate_module_code(&layout, module, &generated, &mut diagnostics);
+                (module, code)
+            })
+            .collect();
+        self.report(diagnostics, log)?;
+        let mut files: Vec<(PathBuf, String)> = modules
+            .into_iter()
+            .map(|(module, code)| (layout.file_path(module), code))
+            .collect();
+        let file_count = files.len();
+        let stale: Vec<PathBuf> = self
+            .read_manifest()?
+            .into_iter()
+            .filter(|owned| files.iter().all(|(file, _)| file != owned))
+            .collect();
+        files.push((PathBuf::from(MANIFEST), render_manifest(&files)));
+
+        if self.settings.check {
+            let mut extra = stale;
+            extra.extend(self.unlisted_python_files(&files, &extra)?);
+            self.check_files(&files, &extra)?;
+            log.info(format!(
+                "{} models in {} files are up to date in {}",
+                generated.len(),
+                file_count,
+                self.settings.output_dir.display()
+            ));
+            return Ok(());
+        }
+        for (relative_path, code) in files.iter() {
+            self.create_pydantic_file(relative_path, code)?;
+            log.trace(format!("wrote {}", relative_path.display()));
+        }
+        for relative_path in stale.iter() {
+            if self.remove_pydantic_file(relative_path)? {
+                log.trace(format!("deleted {}", relative_path.display()));
+            }
+        }
+        log.info(format!(
+            "generated {} models in {} files into {}",
+            generated.len(),
+            file_count,
+            self.settings.output_dir.display()
+        ));
+        Ok(())
+    }
+
+    /// Files written by the previous generation, none without a manifest.
+    fn read_manifest(&self) -> Result<Vec<PathBuf>, Error> {
+        let path = self.settings.output_dir.join(MANIFEST);
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(err) => return Err(Error::io(&path, err)),
+        };
+        Ok(text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            // Entries escaping the output directory aren't owned by the generator
+            .map(PathBuf::from)
+            .filter(|path| {
+                path.components()
+                    .all(|component| matches!(component, Component::Normal(_)))
+            })
+            .collect())
     }
 
-    fn generate_entity_file(&self, meta: &ItemMetadata) {
-        for generator in self.generators.iter() {
-            if generator.is_item_supported(meta) {
-                let config = GeneratorConfig {
-                    header_comment: &self.header_comment,
-                    package_name: &self.package_name,
-                    models_package_name: &self.models_package_name,
+    /// `.py` files of the output directory that are neither generated nor in the manifest, e.g.
+    /// left over from a generation without manifest.
+    fn unlisted_python_files(
+        &self,
+        files: &[(PathBuf, String)],
+        stale: &[PathBuf],
+    ) -> Result<Vec<PathBuf>, Error> {
+        let output_dir = &self.settings.output_dir;
+        let mut unlisted = vec![];
+        let mut pending = vec![output_dir.clone()];
+        while let Some(dir) = pending.pop() {
+            let entries = match fs::read_dir(&dir) {
+                Ok(entries) => entries,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+                Err(err) => return Err(Error::io(&dir, err)),
+            };
+            for entry in entries {
+                let path = entry.map_err(|err| Error::io(&dir, err))?.path();
+                if path.is_dir() {
+                    if path.file_name().is_some_and(|name| name != "__pycache__") {
+                        pending.push(path);
+                    }
+                    continue;
+                }
+                let Ok(relative_path) = path.strip_prefix(output_dir) else {
+                    continue;
                 };
-                let generated_code = generator
-                    .generate(config, &self.collector, meta)
-                    .expect("Generation failed for entity");
-                self.create_pydantic_file(meta.ident(), &generated_code);
+                let listed = files.iter().any(|(file, _)| file == relative_path)
+                    || stale.iter().any(|file| file == relative_path);
+                if !listed && path.extension().is_some_and(|ext| ext == "py") {
+                    unlisted.push(relative_path.to_owned());
+                }
             }
         }
+        unlisted.sort();
+        Ok(unlisted)
     }
 
-    fn generate_init_file(&self) {
-        let mut path = PathBuf::from(&self.base_path);
-        path.push("__init__.py");
-        fs::create_dir_all(path.parent().unwrap()).unwrap();
-        fs::write(&path, self.generate_init_code()).unwrap();
+    /// Fails with the unified diff from the files on disk to the generated files, the `extra`
+    /// files are diffed as deleted.
+    fn check_files(&self, files: &[(PathBuf, String)], extra: &[PathBuf]) -> Result<(), Error> {
+        let output_dir = &self.settings.output_dir;
+        let mut diff = String::new();
+        for (relative_path, code) in files.iter() {
+            let path = output_dir.join(relative_path);
+            let current = match fs::read_to_string(&path) {
+                Ok(text) => Some(text),
+                Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+                Err(err) => return Err(Error::io(&path, err)),
+            };
+            if current.as_deref() != Some(code.as_str()) {
+                diff.push_str(&unified_diff(relative_path, current.as_deref(), Some(code)));
+            }
+        }
+
+        for relative_path in extra.iter() {
+            let path = output_dir.join(relative_path);
+            match fs::read_to_string(&path) {
+                Ok(current) => diff.push_str(&unified_diff(relative_path, Some(&current), None)),
+                Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+                Err(err) => return Err(Error::io(&path, err)),
+            }
+        }
+
+        if diff.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::OutOfDate(diff))
+        }
     }
 
-    fn generate_init_code(&self) -> String {
-        let mut code: Vec<String> = self
-            .collector
-            .entities()
+    fn is_supported(&self, meta: &ItemMetadata) -> bool {
+        self.generators
             .iter()
-            .map(|(k, _)| format!("from .{} import {}", k.to_case(Case::Snake), k))
-            .sorted()
+            .any(|generator| generator.is_item_supported(meta))
+    }
+
+    /// Attaches the entity and where it's defined to the diagnostic.
+    fn locate(&self, diagnostic: Diagnostic, path: &str) -> Diagnostic {
+        let diagnostic = diagnostic.item(path);
+        match self.collector.location(path) {
+            Some((file, line)) => diagnostic.at(file, Some(line).filter(|line| *line > 0)),
+            None => diagnostic,
+        }
+    }
+
+    /// Fails with every diagnostic when one is an error, or with `warnings_as_errors`, else
+    /// prints them as build warnings.
+    fn report(&self, diagnostics: Vec<Diagnostic>, log: &mut GenerationLog) -> Result<(), Error> {
+        let failed = diagnostics.iter().any(|diagnostic| {
+            diagnostic.severity == Severity::Error || self.settings.warnings_as_errors
+        });
+        if failed {
+            return Err(Error::Diagnostics(diagnostics));
+        }
+        for diagnostic in diagnostics.iter() {
+            log.warning(diagnostic.to_string());
+        }
+        Ok(())
+    }
+
+    /// Renders the entities of the module, and the re-exports of its submodules for packages.
+    fn generate_module_code(
+        &self,
+        layout: &PackageLayout,
+        module: &str,
+        generated: &HashMap<&str, GenerationResult>,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) -> String {
+        let mut imports = GenerationResult::default();
+        let mut classes = vec![];
+        // Entities that failed are reported, the module is never written
+        for result in self
+            .sorted_entities(layout, module)
+            .into_iter()
+            .filter_map(|path| generated.get(path))
+        {
+            imports.extend_imports(result);
+            classes.push(result.code.trim_end().to_owned());
+        }
+
+        let mut code = self.settings.header.clone();
+        if !classes.is_empty() {
+            code.push('\n');
+            code.push_str(&render_imports(
+                imports.additional_imports,
+                &imports.type_checking_imports,
+            ));
+            code.push_str("\n\n");
+            code.push_str(&classes.join("\n\n\n"));
+            code.push('\n');
+        }
+        if layout.is_package(module) {
+            code.push_str(if classes.is_empty() { "\n\n" } else { "\n\n\n" });
+            code.push_str(&self.generate_init_code(layout, module, generated, diagnostics));
+            code.push('\n');
+        }
+        code
+    }
+
+    fn generate_entity(
+        &self,
+        layout: &PackageLayout,
+        path: &str,
+    ) -> Result<GenerationResult, Error> {
+        let meta = &self.collector.entities()[path];
+        let config = GeneratorConfig {
+            package_name: &self.settings.package_name,
+            layout,
+            naming: self.settings.naming,
+            python_version: self.settings.python_version,
+            type_mappings: &self.settings.type_mappings,
+            allow_inf_nan: self.settings.allow_inf_nan,
+            f32_precision: self.settings.f32_precision,
+            pointer_width: self.settings.pointer_width,
+        };
+        let generator = self
+            .generators
+            .iter()
+            .find(|generator| generator.is_item_supported(meta))
+            .ok_or_else(|| Error::Generation(format!("no generator supports '{}'", path)))?;
+        generator.generate(config, &self.collector, meta)
+    }
+
+    /// Entities of the module, each after the entities of the module it references at runtime.
+    fn sorted_entities<'a>(&'a self, layout: &'a PackageLayout, module: &str) -> Vec<&'a str> {
+        fn visit<'a>(
+            generator: &'a PydanticGenerator,
+            layout: &'a PackageLayout,
+            path: &'a str,
+            visited: &mut HashSet<&'a str>,
+            sorted: &mut Vec<&'a str>,
+        ) {
+            if !visited.insert(path) {
+                return;
+            }
+            for reference in generator.collector.references(path) {
+                if layout.module(reference) == layout.module(path)
+                    && !layout.is_deferred(&generator.collector, path, reference)
+                {
+                    visit(generator, layout, reference, visited, sorted);
+                }
+            }
+            sorted.push(path);
+        }
+
+        let mut visited = HashSet::new();
+        let mut sorted = vec![];
+        for path in layout.entities(module) {
+            visit(self, layout, path, &mut visited, &mut sorted);
+        }
+        sorted
+    }
+
+    fn generate_init_code(
+        &self,
+        layout: &PackageLayout,
+        package: &str,
+        generated: &HashMap<&str, GenerationResult>,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) -> String {
+        let entities = self.collector.entities();
+        let mut names: HashMap<&str, usize> = HashMap::new();
+        for path in layout
+            .entities(package)
+            .iter()
+            .chain(layout.package_entities(package))
+        {
+            *names.entry(entities[path].name()).or_default() += 1;
+        }
+
+        let mut code: Vec<String> = vec![];
+        let mut exported: Vec<&str> = layout
+            .entities(package)
+            .iter()
+            .map(|path| entities[path].name())
             .collect();
+        for path in layout.package_entities(package) {
+            let name = entities[path].name();
+            if names[name] > 1 {
+                if package.is_empty() {
+                    diagnostics.push(self.locate(
+                        Diagnostic::warning(format!(
+                            "model name '{}' is used in several modules, it isn't re-exported \
+                            by the models package",
+                            name
+                        )),
+                        path,
+                    ));
+                }
+                continue;
+            }
+            let child = child_of(package, layout.module(path)).unwrap();
+            code.push(format!("from .{} import {}", child, name));
+            exported.push(name);
+        }
+        code.sort();
+        code.dedup();
         code.push("\n__all__ = [".to_owned());
         code.extend(
-            self.collector
-                .entities()
-                .iter()
-                .map(|(k, _)| format!("    \"{}\",", k))
-                .sorted(),
+            exported
+                .into_iter()
+                .sorted()
+                .dedup()
+                .map(|name| format!("    \"{}\",", name)),
         );
         code.push("]".to_owned());
+        if package.is_empty() {
+            code.extend(self.generate_rebuild_code(layout));
+            let init_code: Vec<&String> = generated
+                .iter()
+                .sorted_by_key(|(path, _)| **path)
+                .flat_map(|(_, result)| result.init_code.iter())
+                .collect();
+            if !init_code.is_empty() {
+                code.push("".to_owned());
+                code.extend(init_code.into_iter().cloned());
+            }
+        }
         code.join("\n")
     }
 
-    fn create_pydantic_file(&self, ident: &str, generated_code: &str) {
-        println!("cargo:warning=Start Rustantic generator for '{}'", ident);
+    /// Recursive models reference each other with forward references, once every model is
+    /// imported the referenced names are injected in the defining modules and the models rebuilt.
+    fn generate_rebuild_code(&self, layout: &PackageLayout) -> Vec<String> {
+        let entities = self.collector.entities();
+        let mut modules = BTreeSet::new();
+        let mut injections = vec![];
+        for (path, _) in entities.iter().sorted_by_key(|(path, _)| *path) {
+            let module = layout.module(path);
+            for reference in self.collector.references(path) {
+                if layout.module(reference) != module
+                    && layout.is_deferred(&self.collector, path, reference)
+                {
+                    let target = self.module_ref(layout, module, &mut modules);
+                    let value = self.class_ref(layout, reference, &mut modules);
+                    injections.push(format!(
+                        "{}{} = {}",
+                        target,
+                        entities[reference].name(),
+                        value
+                    ));
+                }
+            }
+        }
 
-        let mut path = PathBuf::from(&self.base_path);
-        path.push(self.create_path(ident));
+        let mut rebuilds = vec![];
+        let mut visited = HashMap::new();
+        for path in entities.keys().sorted() {
+            self.collect_rebuilds(layout, path, &mut visited, &mut modules, &mut rebuilds);
+        }
+        if rebuilds.is_empty() {
+            return vec![];
+        }
 
-        fs::create_dir_all(path.parent().unwrap()).unwrap();
-        fs::write(&path, generated_code).unwrap();
+        let mut code = vec![
+            "".to_owned(),
+            "# Resolve the forward references of recursive models".to_owned(),
+        ];
+        code.extend(modules.iter().map(|module| match module.rsplit_once('.') {
+            Some((parent, name)) => {
+                format!("from .{} import {} as {}", parent, name, alias(module))
+            }
+            None => format!("from . import {} as {}", module, alias(module)),
+        }));
+        code.extend(injections);
+        code.extend(rebuilds);
+        code
     }
 
-    fn create_path(&self, identifier: &str) -> String {
-        format!("{}.py", identifier.to_string().to_case(Case::Snake))
+    /// Models are rebuilt after the models they reference, starting with the recursive ones.
+    fn collect_rebuilds<'a>(
+        &'a self,
+        layout: &PackageLayout,
+        path: &'a str,
+        visited: &mut HashMap<&'a str, bool>,
+        modules: &mut BTreeSet<String>,
+        rebuilds: &mut Vec<String>,
+    ) -> bool {
+        if let Some(needs_rebuild) = visited.get(path) {
+            return *needs_rebuild;
+        }
+        let mut needs_rebuild = self
+            .collector
+            .references(path)
+            .any(|reference| layout.is_deferred(&self.collector, path, reference));
+        visited.insert(path, needs_rebuild);
+        for reference in self.collector.references(path) {
+            needs_rebuild |= self.collect_rebuilds(layout, reference, visited, modules, rebuilds);
+        }
+        visited.insert(path, needs_rebuild);
+        if !needs_rebuild {
+            return false;
+        }
+
+        match self.collector.entities().get(path) {
+            Some(ItemMetadata::Struct(_)) => {
+                let class = self.class_ref(layout, path, modules);
+                rebuilds.push(format!("{}.model_rebuild()", class));
+            }
+            Some(ItemMetadata::DiscriminatedUnion(meta)) => {
+                let module = self.module_ref(layout, layout.module(path), modules);
+                for variant in meta.variants.iter() {
+                    rebuilds.push(format!(
+                        "{}{}.model_rebuild()",
+                        module,
+                        meta.variant_name(variant)
+                    ));
+                }
+                let class = self.class_ref(layout, path, modules);
+                rebuilds.push(format!("{}.model_rebuild()", class));
+            }
+            _ => {}
+        }
+        true
+    }
+
+    /// Prefix of the names defined by the module in the models package `__init__.py`.
+    fn module_ref(
+        &self,
+        layout: &PackageLayout,
+        module: &str,
+        modules: &mut BTreeSet<String>,
+    ) -> String {
+        if layout.layout() == Layout::Modules && module.is_empty() {
+            return String::new();
+        }
+        modules.insert(module.to_owned());
+        format!("{}.", alias(module))
+    }
+
+    /// The entity class in the models package `__init__.py`, flat and single file models are
+    /// all re-exported.
+    fn class_ref(
+        &self,
+        layout: &PackageLayout,
+        path: &str,
+        modules: &mut BTreeSet<String>,
+    ) -> String {
+        let name = self.collector.entities()[path].name();
+        match layout.layout() {
+            Layout::Flat | Layout::SingleFile => name.to_owned(),
+            Layout::Modules => format!(
+                "{}{}",
+                self.module_ref(layout, layout.module(path), modules),
+                name
+            ),
+        }
+    }
+
+    fn create_pydantic_file(
+        &self,
+        relative_path: &Path,
+        generated_code: &str,
+    ) -> Result<(), Error> {
+        let path = self.settings.output_dir.join(relative_path);
+        let dir = path.parent().unwrap();
+        fs::create_dir_all(dir).map_err(|err| Error::io(dir, err))?;
+        fs::write(&path, generated_code).map_err(|err| Error::io(&path, err))
     }
+
+    /// Removes a file the generator no longer produces, and the directories it leaves empty.
+    /// Returns whether the file existed.
+    fn remove_pydantic_file(&self, relative_path: &Path) -> Result<bool, Error> {
+        let output_dir = &self.settings.output_dir;
+        let path = output_dir.join(relative_path);
+        match fs::remove_file(&path) {
+            Ok(()) => {}
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(false),
+            Err(err) => return Err(Error::io(&path, err)),
+        }
+        let mut dir = path.parent();
+        while let Some(current) = dir.filter(|dir| dir != output_dir) {
+            let is_empty = fs::read_dir(current)
+                .map_err(|err| Error::io(current, err))?
+                .next()
+                .is_none();
+            if !is_empty {
+                break;
+            }
+            fs::remove_dir(current).map_err(|err| Error::io(current, err))?;
+            dir = current.parent();
+        }
+        Ok(true)
+    }
+}
+
+/// Lists the files written by a generation, relative to the output directory. Files listed by
+/// the previous generation but not generated anymore are deleted.
+const MANIFEST: &str = ".rustantic-manifest";
+
+fn render_manifest(files: &[(PathBuf, String)]) -> String {
+    let mut manifest = "# Files generated by rustantic, the generator deletes them once they \
+        aren't generated anymore\n"
+        .to_owned();
+    for (relative_path, _) in files.iter().sorted_by(|a, b| a.0.cmp(&b.0)) {
+        manifest.push_str(&relative_path.display().to_string().replace('\\', "/"));
+        manifest.push('\n');
+    }
+    manifest
+}
+
+/// Diff of a file of the models package, `None` when it doesn't exist.
+fn unified_diff(relative_path: &Path, old: Option<&str>, new: Option<&str>) -> String {
+    let name = relative_path.display().to_string().replace('\\', "/");
+    let old_header = old.map_or("/dev/null".to_owned(), |_| format!("a/{}", name));
+    let new_header = new.map_or("/dev/null".to_owned(), |_| format!("b/{}", name));
+    TextDiff::from_lines(old.unwrap_or_default(), new.unwrap_or_default())
+        .unified_diff()
+        .header(&old_header, &new_header)
+        .missing_newline_hint(false)
+        .to_string()
+}
+
+/// Name the models package `__init__.py` imports the module as.
+fn alias(module: &str) -> String {
+    format!("_{}", module.replace('.', "_"))
 }
@@ -1,29 +1,226 @@
-use crate::{collector::MetadataCollector, models::ItemMetadata};
-use std::collections::HashSet;
+use super::layout::PackageLayout;
+use crate::{
+    attributes::{ContainerAttributes, FieldAttributes},
+    builder::{F32Precision, Naming, PythonVersion},
+    collector::MetadataCollector,
+    error::Error,
+    models::{ItemMetadata, MissingFromRs},
+    type_mapping::TypeMapping,
+};
+use itertools::Itertools;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
+/// Code generated for an item, the file header and import block are rendered from the
+/// imports of every item of the module.
 #[derive(Default)]
-pub(crate) struct GenerationResult {
+#[non_exhaustive]
+pub struct GenerationResult {
     pub code: String,
+    /// Import lines, e.g. `from pydantic import BaseModel`.
     pub additional_imports: HashSet<String>,
+    /// Imports of recursive references, only needed by type checkers.
+    pub type_checking_imports: HashSet<String>,
+    /// Statements run by the models package `__init__.py` once every model is imported.
+    pub init_code: Vec<String>,
+    /// Reported as build warnings.
+    pub warnings: Vec<String>,
 }
 
+impl GenerationResult {
+    pub fn new(code: impl Into<String>) -> Self {
+        Self {
+            code: code.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Whether the model gets a `from_rs()`, the ones that can't have one are warned about.
+    pub(crate) fn check_from_rs(&mut self, missing: Option<MissingFromRs>) -> bool {
+        match missing {
+            None => true,
+            Some(MissingFromRs::NoGetters) => false,
+            Some(MissingFromRs::Unreadable(reason)) => {
+                self.warnings
+                    .push(format!("from_rs() is not generated, {}", reason));
+                false
+            }
+        }
+    }
+
+    pub fn extend_imports(&mut self, other: &GenerationResult) {
+        self.additional_imports
+            .extend(other.additional_imports.iter().cloned());
+        self.type_checking_imports
+            .extend(other.type_checking_imports.iter().cloned());
+    }
+}
+
+/// Renders the sorted import lines, followed by an `if TYPE_CHECKING:` block if needed.
+pub(crate) fn render_imports(
+    mut imports: HashSet<String>,
+    type_checking_imports: &HashSet<String>,
+) -> String {
+    if !type_checking_imports.is_empty() {
+        imports.insert("from typing import TYPE_CHECKING".to_owned());
+    }
+    let mut code = merge_imports(&imports).join("\n");
+    if !type_checking_imports.is_empty() {
+        code.push_str("\n\nif TYPE_CHECKING:\n");
+        code.push_str(
+            &merge_imports(type_checking_imports)
+                .iter()
+                .map(|import| format!("    {}", import))
+                .join("\n"),
+        );
+    }
+    code
+}
+
+/// Merges the `from module import ...` lines of the same module, sorted and deduplicated.
+fn merge_imports(imports: &HashSet<String>) -> Vec<String> {
+    let mut from_imports: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    let mut lines = vec![];
+    for import in imports {
+        let from_import = import
+            .strip_prefix("from ")
+            .and_then(|import| import.split_once(" import "));
+        match from_import {
+            Some((module, names)) => from_imports
+                .entry(module)
+                .or_default()
+                .extend(names.split(',').map(str::trim)),
+            None => lines.push(import.clone()),
+        }
+    }
+    lines.extend(
+        from_imports
+            .into_iter()
+            .map(|(module, names)| format!("from {} import {}", module, names.iter().join(", "))),
+    );
+    lines.sort();
+    lines
+}
+
+/// Options of the generation, passed to the generators.
 #[derive(Clone)]
-pub(crate) struct GeneratorConfig<'a> {
-    pub header_comment: &'a str,
-    pub package_name: &'a str,
-    pub models_package_name: &'a str,
+pub struct GeneratorConfig<'a> {
+    pub(crate) package_name: &'a str,
+    pub(crate) layout: &'a PackageLayout,
+    pub(crate) naming: Naming,
+    pub(crate) python_version: PythonVersion,
+    pub(crate) type_mappings: &'a BTreeMap<String, TypeMapping>,
+    pub(crate) allow_inf_nan: bool,
+    pub(crate) f32_precision: F32Precision,
+    /// Bits of `isize` and `usize`.
+    pub(crate) pointer_width: u32,
 }
 
-pub(crate) trait PydanticCodeGenerator {
+impl GeneratorConfig<'_> {
+    /// Python module exposing the pyclasses.
+    pub fn package_name(&self) -> &str {
+        self.package_name
+    }
+
+    pub fn naming(&self) -> Naming {
+        self.naming
+    }
+
+    pub fn python_version(&self) -> PythonVersion {
+        self.python_version
+    }
+
+    /// Python module exposing the pyclass of an item.
+    pub fn pyo3_module<'b>(&'b self, attrs: &'b ContainerAttributes) -> &'b str {
+        attrs.module.as_deref().unwrap_or(self.package_name)
+    }
+
+    /// Python name of a field, `#[pydantic(rename = "...")]` or the ident following the naming.
+    pub fn field_name(&self, ident: &str, attrs: &FieldAttributes) -> String {
+        attrs
+            .rename
+            .clone()
+            .unwrap_or_else(|| self.naming.apply(ident))
+    }
+
+    /// Attribute of a field on the model. Pydantic makes names with a leading underscore
+    /// private attributes, they are stripped and the field keeps its Python name as alias.
+    pub fn field_attribute(&self, ident: &str, attrs: &FieldAttributes) -> String {
+        self.field_name(ident, attrs)
+            .trim_start_matches('_')
+            .to_owned()
+    }
+
+    /// Fails when the leading underscores of a field were all its name, or when two fields
+    /// end up with the same attribute, e.g. `_id` and `id`.
+    pub(crate) fn check_field_attributes<'b>(
+        &self,
+        fields: impl IntoIterator<Item = (&'b str, &'b FieldAttributes)>,
+    ) -> Result<(), Error> {
+        let mut attributes: HashMap<String, String> = HashMap::new();
+        for (ident, attrs) in fields {
+            let field_name = self.field_name(ident, attrs);
+            let attribute = self.field_attribute(ident, attrs);
+            if attribute.is_empty() {
+                return Err(Error::Generation(format!(
+                    "field '{}' has no name once its leading underscores are removed, rename it \
+                     with #[pydantic(rename = \"...\")]",
+                    field_name
+                )));
+            }
+            if let Some(other) = attributes.insert(attribute.clone(), field_name.clone()) {
+                return Err(Error::Generation(format!(
+                    "fields '{}' and '{}' are both generated as '{}', rename one of them with \
+                     #[pydantic(rename = \"...\")]",
+                    other, field_name, attribute
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Renders the `model_config` line for the item arguments, if any is set.
+/// `RootModel` doesn't support `extra`, it's left out for root models.
+pub(crate) fn generate_model_config(
+    attrs: &ContainerAttributes,
+    root_model: bool,
+) -> Option<GenerationResult> {
+    let mut options = vec![];
+    if attrs.frozen {
+        options.push("frozen=True".to_owned());
+    }
+    if let Some(extra) = attrs.extra.filter(|_| !root_model) {
+        options.push(format!("extra=\"{}\"", extra.as_str()));
+    }
+
+    if options.is_empty() {
+        None
+    } else {
+        Some(GenerationResult {
+            code: format!("    model_config = ConfigDict({})\n", options.join(", ")),
+            additional_imports: HashSet::from(["from pydantic import ConfigDict".to_owned()]),
+            ..Default::default()
+        })
+    }
+}
+
+/// Generates the Python code of an item kind, registered with
+/// `Rustantic::builder().register::<T>()`.
+pub trait PydanticCodeGenerator {
+    /// Whether the generator handles the item, the first registered generator handling an item
+    /// generates it.
     fn is_item_supported(&self, meta: &ItemMetadata) -> bool;
+
+    /// Generates the model of the item. `collector` holds every collected item, e.g. to resolve
+    /// the types of the fields, `FieldGenerator` maps them like the built-in generators do.
     fn generate(
         &self,
         config: GeneratorConfig,
         collector: &MetadataCollector,
         meta: &ItemMetadata,
-    ) -> Result<String, ()>;
+    ) -> Result<GenerationResult, Error>;
 }
 
-pub(crate) trait PydanticCodeGeneratorFactory {
+pub trait PydanticCodeGeneratorFactory {
     fn create() -> Box<dyn PydanticCodeGenerator>;
 }
@@ -0,0 +1,245 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::PathBuf;
+
+use convert_case::{Case, Casing};
+use itertools::Itertools;
+
+use crate::{collector::MetadataCollector, models::ItemMetadata};
+
+/// How the generated models are split into Python modules.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Layout {
+    /// A module per model in the models package, e.g. `generated/invoice.py`.
+    #[default]
+    Flat,
+    /// A module per Rust module, `crate::billing::invoice::Invoice` is generated in
+    /// `generated/billing/invoice.py`. Models of a module with submodules, like the crate root,
+    /// are generated in the `__init__.py` of its package.
+    Modules,
+    /// Every model in a single `generated/models.py`, sorted so models are defined before the
+    /// models referencing them.
+    SingleFile,
+}
+
+impl Layout {
+    /// Python module of the item relative to the models package, `""` for the package itself.
+    pub(crate) fn module_name(&self, meta: &ItemMetadata) -> String {
+        match self {
+            Layout::Flat => meta.name().to_case(Case::Snake),
+            Layout::SingleFile => "models".to_owned(),
+            Layout::Modules => meta
+                .module()
+                .split("::")
+                .skip(1)
+                .map(|segment| segment.trim_start_matches("r#"))
+                .join("."),
+        }
+    }
+}
+
+/// Python modules of the collected entities and the imports between them.
+pub(crate) struct PackageLayout {
+    layout: Layout,
+    models_package_name: String,
+    /// Module of each entity keyed by the entity path.
+    modules: HashMap<String, String>,
+    /// Entities of each module, sorted by name.
+    entities: BTreeMap<String, Vec<String>>,
+    /// Modules with submodules, the models package `""` included.
+    packages: BTreeSet<String>,
+    /// Other modules referenced by the entities of each module.
+    references: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl PackageLayout {
+    pub fn new(layout: Layout, models_package_name: &str, collector: &MetadataCollector) -> Self {
+        let modules: HashMap<String, String> = collector
+            .entities()
+            .iter()
+            .map(|(path, meta)| (path.clone(), layout.module_name(meta)))
+            .collect();
+
+        let mut entities: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for (path, module) in modules.iter() {
+            entities
+                .entry(module.clone())
+                .or_default()
+                .push(path.clone());
+        }
+        for paths in entities.values_mut() {
+            paths.sort_by_key(|path| (collector.entities()[path].name(), path.clone()));
+        }
+
+        let mut packages = BTreeSet::from([String::new()]);
+        for module in entities.keys() {
+            packages.extend(ancestors(module).map(str::to_owned));
+        }
+
+        let mut references: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        for (path, module) in modules.iter() {
+            for reference in collector.references(path) {
+                let referenced = &modules[reference];
+                if referenced != module {
+                    references
+                        .entry(module.clone())
+                        .or_default()
+                        .insert(referenced.clone());
+                }
+            }
+        }
+
+        Self {
+            layout,
+            models_package_name: models_package_name.to_owned(),
+            modules,
+            entities,
+            packages,
+            references,
+        }
+    }
+
+    pub fn layout(&self) -> Layout {
+        self.layout
+    }
+
+    /// Module of the entity, relative to the models package.
+    pub fn module(&self, path: &str) -> &str {
+        &self.modules[path]
+    }
+
+    /// Absolute name of the module defining the entity, as used in imports.
+    pub fn qualified_module(&self, path: &str) -> String {
+        match self.module(path) {
+            "" => self.models_package_name.clone(),
+            module => format!("{}.{}", self.models_package_name, module),
+        }
+    }
+
+    /// Every module to generate, packages included.
+    pub fn all_modules(&self) -> BTreeSet<&str> {
+        self.entities
+            .keys()
+            .chain(self.packages.iter())
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Entities defined in the module, sorted by name.
+    pub fn entities(&self, module: &str) -> &[String] {
+        self.entities.get(module).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Entities defined under the package, not in the package itself.
+    pub fn package_entities<'a>(&'a self, package: &'a str) -> impl Iterator<Item = &'a String> {
+        self.entities
+            .iter()
+            .filter(move |(module, _)| child_of(package, module).is_some())
+            .flat_map(|(_, paths)| paths.iter())
+    }
+
+    pub fn is_package(&self, module: &str) -> bool {
+        self.packages.contains(module)
+    }
+
+    /// File of the module, relative to the models package directory.
+    pub fn file_path(&self, module: &str) -> PathBuf {
+        let mut path: PathBuf = module.split('.').filter(|s| !s.is_empty()).collect();
+        if self.is_package(module) {
+            path.push("__init__.py");
+        } else {
+            path.set_extension("py");
+        }
+        path
+    }
+
+    /// Whether the reference from the entity `from` to the entity `to` must be a forward
+    /// reference, resolved once every module is imported.
+    ///
+    /// Within a module it's needed by recursive references. Across modules it's needed when
+    /// importing the module of `to` may end up importing the names of the module of `from`
+    /// before it defined them, either through the references of the modules or through the
+    /// packages re-exporting their submodules.
+    pub fn is_deferred(&self, collector: &MetadataCollector, from: &str, to: &str) -> bool {
+        let (from_module, to_module) = (self.module(from), self.module(to));
+        if from_module == to_module {
+            return collector.is_recursive_reference(from, to);
+        }
+
+        // Modules being imported while `from_module` imports its references aren't imported
+        // again, neither are the packages containing it
+        let mut visited: BTreeSet<&str> = ancestors(from_module).collect();
+        visited.insert(from_module);
+        let mut pending = vec![];
+        self.push_imported(from_module, to_module, &mut visited, &mut pending);
+        while let Some(module) = pending.pop() {
+            if self
+                .references
+                .get(module)
+                .is_some_and(|references| references.contains(from_module))
+            {
+                return true;
+            }
+            for reference in self.references.get(module).into_iter().flatten() {
+                self.push_imported(module, reference, &mut visited, &mut pending);
+            }
+            if self.is_package(module) {
+                for child in self.all_modules() {
+                    if parent(child) == Some(module) {
+                        self.push_imported(module, child, &mut visited, &mut pending);
+                    }
+                }
+            }
+        }
+        false
+    }
+
+    /// Queues `to` and its packages not yet imported when `from` imports it.
+    fn push_imported<'a>(
+        &self,
+        from: &str,
+        to: &'a str,
+        visited: &mut BTreeSet<&'a str>,
+        pending: &mut Vec<&'a str>,
+    ) {
+        let from_ancestors: BTreeSet<&str> = ancestors(from).collect();
+        for module in ancestors(to).chain([to]) {
+            if !from_ancestors.contains(module) && visited.insert(module) {
+                pending.push(module);
+            }
+        }
+    }
+}
+
+/// Packages containing the module, the models package first.
+fn ancestors(module: &str) -> impl Iterator<Item = &str> {
+    let ends: Vec<usize> = if module.is_empty() {
+        vec![]
+    } else {
+        [0].into_iter()
+            .chain(module.match_indices('.').map(|(idx, _)| idx))
+            .collect()
+    };
+    ends.into_iter().map(move |end| &module[..end])
+}
+
+/// Name of the child of `package` containing `module`, if `module` is under `package`.
+pub(crate) fn child_of<'a>(package: &str, module: &'a str) -> Option<&'a str> {
+    let rest = if package.is_empty() {
+        module
+    } else {
+        module.strip_prefix(package)?.strip_prefix('.')?
+    };
+    if rest.is_empty() {
+        None
+    } else {
+        Some(rest.split('.').next().unwrap())
+    }
+}
+
+fn parent(module: &str) -> Option<&str> {
+    if module.is_empty() {
+        None
+    } else {
+        Some(module.rsplit_once('.').map_or("", |(parent, _)| parent))
+    }
+}
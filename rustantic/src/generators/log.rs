@@ -0,0 +1,50 @@
+use std::fs;
+use std::path::Path;
+
+use crate::builder::Verbosity;
+use crate::error::Error;
+
+/// Progress of a generation, printed as build warnings up to the verbosity and kept whole for
+/// the log file.
+pub(crate) struct GenerationLog {
+    verbosity: Verbosity,
+    lines: Vec<String>,
+}
+
+impl GenerationLog {
+    pub fn new(verbosity: Verbosity) -> Self {
+        Self {
+            verbosity,
+            lines: vec![],
+        }
+    }
+
+    pub fn info(&mut self, message: impl Into<String>) {
+        self.push(Verbosity::Info, message.into());
+    }
+
+    pub fn trace(&mut self, message: impl Into<String>) {
+        self.push(Verbosity::Trace, message.into());
+    }
+
+    /// Problems are printed whatever the verbosity.
+    pub fn warning(&mut self, message: impl Into<String>) {
+        self.push(Verbosity::Quiet, message.into());
+    }
+
+    fn push(&mut self, level: Verbosity, message: String) {
+        if level <= self.verbosity {
+            println!("cargo:warning=Rustantic {}", message);
+        }
+        self.lines.push(message);
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|err| Error::io(dir, err))?;
+        }
+        let mut text = self.lines.join("\n");
+        text.push('\n');
+        fs::write(path, text).map_err(|err| Error::io(path, err))
+    }
+}
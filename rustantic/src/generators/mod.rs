@@ -2,25 +2,27 @@ pub mod enum_generator;
 pub mod field_generator;
 pub mod generator;
 pub mod generator_base;
+pub mod layout;
+mod log;
 pub mod struct_generator;
 pub mod union_generator;
 
-pub(crate) use enum_generator::EnumCodeGenerator;
-pub(crate) use struct_generator::StructCodeGenerator;
-pub(crate) use union_generator::UnionCodeGenerator;
+pub use enum_generator::EnumCodeGenerator;
+pub use field_generator::FieldGenerator;
+pub use generator_base::{
+    GenerationResult, GeneratorConfig, PydanticCodeGenerator, PydanticCodeGeneratorFactory,
+};
+pub use layout::Layout;
+pub use struct_generator::StructCodeGenerator;
+pub use union_generator::UnionCodeGenerator;
 
+#[deprecated(note = "use `rustantic::Rustantic::builder()`")]
 pub fn generate(package_name: &str, base_path: &str, lib_path: &str, models_package_name: &str) {
-    use crate::collector;
-
-    let mut generator = generator::PydanticGenerator::new(
-        package_name.to_string(),
-        base_path.to_string(),
-        models_package_name.to_string(),
-        collector::MetadataCollector::new(lib_path),
-    )
-    .register::<StructCodeGenerator>()
-    .register::<EnumCodeGenerator>()
-    .register::<UnionCodeGenerator>();
-
-    generator.generate();
+    crate::Rustantic::builder()
+        .package_name(package_name)
+        .output_dir(base_path)
+        .crate_dir(lib_path)
+        .models_package_name(models_package_name)
+        .generate()
+        .unwrap_or_else(|err| panic!("Rustantic generation failed: {}", err));
 }
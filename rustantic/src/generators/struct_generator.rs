@@ -1,18 +1,18 @@
-use std::collections::HashSet;
-
-use super::field_generator::FieldGenerator;
+use super::field_generator::{option_inner_type, FieldGenerator};
+use super::generator_base::generate_model_config;
 use super::generator_base::GenerationResult;
 use super::generator_base::GeneratorConfig;
 use super::generator_base::PydanticCodeGenerator;
 use super::generator_base::PydanticCodeGeneratorFactory;
 use crate::collector::MetadataCollector;
+use crate::error::Error;
+use crate::models::ArgKind;
 use crate::models::ConstructorMetadata;
 use crate::models::ItemMetadata;
 use crate::models::StructMetadata;
-use itertools::sorted;
-use itertools::Itertools;
+use syn::{Fields, FieldsUnnamed, Type, TypeTuple};
 
-pub(crate) struct StructCodeGenerator {}
+pub struct StructCodeGenerator {}
 
 impl PydanticCodeGeneratorFactory for StructCodeGenerator {
     fn create() -> Box<dyn PydanticCodeGenerator> {
@@ -30,83 +30,151 @@ impl PydanticCodeGenerator for StructCodeGenerator {
         config: GeneratorConfig,
         collector: &MetadataCollector,
         meta: &ItemMetadata,
-    ) -> Result<String, ()> {
+    ) -> Result<GenerationResult, Error> {
         if let ItemMetadata::Struct(struct_md) = meta {
+            self.check_skipped_args(struct_md)?;
+            self.check_field_attributes(&config, struct_md)?;
             Ok(self.generate_code(config, collector, struct_md))
         } else {
-            Err(())
+            Err(Error::Generation(format!(
+                "'{}' is not a struct",
+                meta.path()
+            )))
         }
     }
 }
 
 impl StructCodeGenerator {
+    /// `to_rs()` leaves skipped fields out, so they must be optional arguments of `#[new]`.
+    fn check_skipped_args(&self, meta: &StructMetadata) -> Result<(), Error> {
+        let Some(ref ctor) = meta.constructor else {
+            return Ok(());
+        };
+        let skipped_required = ctor.args.iter().find(|arg| {
+            let required =
+                arg.default.is_none() && !matches!(arg.kind, ArgKind::VarArgs | ArgKind::VarKwargs);
+            required && meta.field_attrs(&arg.name).skip
+        });
+        match skipped_required {
+            Some(arg) => Err(Error::Generation(format!(
+                "`skip` on '{}' leaves out a required argument of the #[new] constructor, give it \
+                 a default in the pyo3 signature",
+                arg.name
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    fn check_field_attributes(
+        &self,
+        config: &GeneratorConfig,
+        meta: &StructMetadata,
+    ) -> Result<(), Error> {
+        let idents: Vec<String> = match meta.constructor {
+            Some(ref ctor) => ctor.args.iter().map(|arg| arg.name.clone()).collect(),
+            None => meta
+                .fields
+                .iter()
+                .filter_map(|field| Some(field.ident.as_ref()?.to_string()))
+                .collect(),
+        };
+        config.check_field_attributes(
+            idents
+                .iter()
+                .map(|ident| (ident.as_str(), meta.field_attrs(ident)))
+                .filter(|(_, attrs)| !attrs.skip),
+        )
+    }
+
     fn generate_code(
         &self,
         config: GeneratorConfig,
         collector: &MetadataCollector,
         meta: &StructMetadata,
-    ) -> String {
-        let field_generator = FieldGenerator::new(config.clone(), collector.entities());
-
-        let class_declaration = format!("class {}(BaseModel):", &meta.ident);
-        let class_definition = self.generate_definition(&config, &field_generator, meta);
-        let import_code =
-            self.generate_import(config.package_name, &class_definition.additional_imports);
-
-        format!(
-            "{}\n{}\n\n{}\n{}",
-            config.header_comment, import_code, class_declaration, class_definition.code
-        )
-    }
+    ) -> GenerationResult {
+        let path = meta.path();
+        let field_generator = FieldGenerator::new(config.clone(), collector, &path);
 
-    fn generate_import(&self, package_name: &str, additional_imports: &HashSet<String>) -> String {
-        let mut imports: HashSet<String> = vec![
+        let (class_declaration, mut class_definition) = match meta.fields {
+            Fields::Named(_) => (
+                format!("class {}(BaseModel):", meta.name()),
+                self.generate_definition(&config, collector, &field_generator, meta),
+            ),
+            Fields::Unnamed(ref fields_unnamed) => self.generate_root_definition(
+                &config,
+                collector,
+                &field_generator,
+                meta,
+                fields_unnamed,
+            ),
+            Fields::Unit => (
+                format!("class {}(BaseModel):", meta.name()),
+                self.generate_unit_definition(&config, meta),
+            ),
+        };
+        let is_root_model = matches!(meta.fields, Fields::Unnamed(_));
+        if let Some(model_config) = generate_model_config(&meta.attrs, is_root_model) {
+            class_definition.code.insert_str(0, &model_config.code);
+            class_definition.extend_imports(&model_config);
+        }
+        class_definition.additional_imports.extend([
             "from pydantic import BaseModel, Field".to_owned(),
-            format!("import {}", package_name),
-        ]
-        .into_iter()
-        .collect();
-
-        imports.extend(additional_imports.iter().map(|i| i.to_owned()));
-
-        sorted(imports).join("\n")
+            format!("import {}", config.pyo3_module(&meta.attrs)),
+        ]);
+        class_definition.code = format!("{}\n{}", class_declaration, class_definition.code);
+        class_definition
     }
 
     fn generate_definition(
         &self,
         config: &GeneratorConfig,
+        collector: &MetadataCollector,
         field_generator: &FieldGenerator,
         meta: &StructMetadata,
     ) -> GenerationResult {
-        if let Some(ref ctor) = meta.constructor {
-            self.generate_ctor_based_definition(config, field_generator, &meta.ident, ctor)
+        let mut result = if let Some(ref ctor) = meta.constructor {
+            self.generate_ctor_based_definition(config, field_generator, meta, ctor)
         } else {
             self.generate_fields_based_definition(field_generator, meta)
+        };
+
+        let has_from_rs = result.check_from_rs(collector.missing_from_rs(&meta.path()));
+        if let (true, Fields::Named(_)) = (has_from_rs, &meta.fields) {
+            if !result.code.ends_with('\n') {
+                result.code.push('\n');
+            }
+            result.code.push('\n');
+            result
+                .code
+                .push_str(&self.generate_from_pyo3(field_generator, meta));
         }
+        result
     }
 
     fn generate_ctor_based_definition(
         &self,
         config: &GeneratorConfig,
         field_generator: &FieldGenerator,
-        ident: &str,
+        meta: &StructMetadata,
         ctor: &ConstructorMetadata,
     ) -> GenerationResult {
         let mut result = GenerationResult::default();
-        for (arg_name, arg_ty) in ctor.args.iter() {
-            let field_result = field_generator.generate(&arg_name, arg_ty);
-            result
-                .additional_imports
-                .extend(field_result.additional_imports);
+        for arg in ctor.args.iter() {
+            let attrs = meta.field_attrs(&arg.name);
+            if attrs.skip {
+                continue;
+            }
+            let field_result = field_generator.generate_arg(arg, attrs);
+            result.extend_imports(&field_result);
             result
                 .code
                 .push_str(&format!("    {}\n", field_result.code));
         }
-        result.code.push_str("\n");
+        result.code.push('\n');
         result.code.push_str(&self.generate_to_pyo3(
-            &field_generator,
-            config.package_name,
-            &ident,
+            field_generator,
+            config.pyo3_module(&meta.attrs),
+            meta,
             ctor,
         ));
         result
@@ -118,58 +186,246 @@ impl StructCodeGenerator {
         meta: &StructMetadata,
     ) -> GenerationResult {
         let mut result = GenerationResult::default();
-        if let syn::Fields::Named(ref fields_named) = meta.fields {
-            for field in fields_named.named.iter() {
-                let field_ident = field
-                    .ident
-                    .as_ref()
-                    .expect("Identifier cannot be empty for named fields")
-                    .to_string();
-                let field_result = field_generator.generate(&field_ident, &field.ty);
-                result
-                    .additional_imports
-                    .extend(field_result.additional_imports);
-                result
-                    .code
-                    .push_str(&format!("    {}\n", field_result.code));
+        for field in meta.fields.iter() {
+            let field_ident = field
+                .ident
+                .as_ref()
+                .expect("Identifier cannot be empty for named fields")
+                .to_string();
+            let attrs = meta.field_attrs(&field_ident);
+            if attrs.skip {
+                continue;
             }
-        } else {
-            result.code = self.generate_invalid_model_body(&meta.ident, "No pyo3 constructor");
+            let field_result = field_generator.generate(&field_ident, &field.ty, attrs);
+            result.extend_imports(&field_result);
+            result
+                .code
+                .push_str(&format!("    {}\n", field_result.code));
         }
 
         result
     }
 
-    fn generate_invalid_model_body(&self, ident: &str, error: &str) -> String {
-        println!(
-            "cargo::warning=Rustantic '{}' generation failed, error: {}",
-            ident, error
-        );
-        let mut code = format!("    # Invalid model definition: {}\n", error);
-        code.push_str("    pass\n");
+    /// Tuple structs become a `RootModel`, of the inner type for newtypes and of a tuple otherwise.
+    fn generate_root_definition(
+        &self,
+        config: &GeneratorConfig,
+        collector: &MetadataCollector,
+        field_generator: &FieldGenerator,
+        meta: &StructMetadata,
+        fields: &FieldsUnnamed,
+    ) -> (String, GenerationResult) {
+        let is_newtype = fields.unnamed.len() == 1;
+        let mut result = GenerationResult::default();
+        let root_type = if is_newtype {
+            let root_ty = &fields.unnamed[0].ty;
+            let root_field = field_generator.generate("root", root_ty, meta.field_attrs("0"));
+            result.extend_imports(&root_field);
+            result.code.push_str(&format!("    {}\n", root_field.code));
+            field_generator.generate_type(root_ty)
+        } else if fields.unnamed.is_empty() {
+            let root_type = field_generator.generate_type(&Type::Tuple(TypeTuple {
+                paren_token: Default::default(),
+                elems: Default::default(),
+            }));
+            result
+                .code
+                .push_str(&format!("    root: {}\n", root_type.code));
+            root_type
+        } else {
+            // The field arguments of each item apply to it within the tuple
+            let mut root_type = GenerationResult::default();
+            let items: Vec<String> = fields
+                .unnamed
+                .iter()
+                .enumerate()
+                .map(|(idx, field)| {
+                    let item = field_generator
+                        .generate_annotated(&field.ty, meta.field_attrs(&idx.to_string()));
+                    root_type.extend_imports(&item);
+                    item.code
+                })
+                .collect();
+            root_type.code = format!("tuple[{}]", items.join(", "));
+            result
+                .code
+                .push_str(&format!("    root: {}\n", root_type.code));
+            root_type
+        };
+        result.extend_imports(&root_type);
+        result
+            .additional_imports
+            .insert("from pydantic import RootModel".to_owned());
+
+        let (to_rs_args, from_rs_args): (Vec<String>, Vec<String>) = fields
+            .unnamed
+            .iter()
+            .enumerate()
+            .map(|(idx, field)| {
+                let root_item = if is_newtype {
+                    "self.root".to_owned()
+                } else {
+                    format!("self.root[{}]", idx)
+                };
+                (
+                    field_generator.generate_to_pyo3(&root_item, &field.ty),
+                    field_generator.generate_from_pyo3(&format!("obj._{}", idx), &field.ty),
+                )
+            })
+            .unzip();
+        let from_rs_root = if !result.check_from_rs(collector.missing_from_rs(&meta.path())) {
+            None
+        } else if is_newtype {
+            Some(from_rs_args.join(""))
+        } else {
+            Some(format!("({})", from_rs_args.join(", ")))
+        };
+        result.code.push_str(&self.generate_positional_conversions(
+            config,
+            meta,
+            &to_rs_args,
+            from_rs_root.as_deref(),
+        ));
+
+        let declaration = format!("class {}(RootModel[{}]):", meta.name(), root_type.code);
+        (declaration, result)
+    }
+
+    fn generate_unit_definition(
+        &self,
+        config: &GeneratorConfig,
+        meta: &StructMetadata,
+    ) -> GenerationResult {
+        let mut result = GenerationResult::default();
+        if meta.constructor.is_none() {
+            result.code.push_str("    pass\n");
+        }
+        let conversions = self.generate_positional_conversions(config, meta, &[], Some(""));
+        result.code.push_str(if result.code.is_empty() {
+            conversions.trim_start_matches('\n')
+        } else {
+            &conversions
+        });
+        result
+    }
+
+    /// `to_rs()` passing `to_rs_args` positionally (only with a constructor) and `from_rs()`
+    /// passing `from_rs_arg`, if it has one.
+    fn generate_positional_conversions(
+        &self,
+        config: &GeneratorConfig,
+        meta: &StructMetadata,
+        to_rs_args: &[String],
+        from_rs_arg: Option<&str>,
+    ) -> String {
+        let indent = "    ";
+        let mut code = String::new();
+        if meta.constructor.is_some() {
+            code.push_str(&format!(
+                "\n{0}def to_rs(self):\n{0}{0}return {1}.{2}({3})\n",
+                indent,
+                config.pyo3_module(&meta.attrs),
+                meta.name(),
+                to_rs_args.join(", ")
+            ));
+        }
+        if let Some(from_rs_arg) = from_rs_arg {
+            code.push_str(&format!(
+                "\n{0}@classmethod\n{0}def from_rs(cls, obj):\n{0}{0}return cls({1})",
+                indent, from_rs_arg
+            ));
+        }
         code
     }
 
     fn generate_to_pyo3(
         &self,
         field_generator: &FieldGenerator,
-        package_name: &str,
-        struct_name: &str,
+        pyo3_module: &str,
+        meta: &StructMetadata,
         ctor: &ConstructorMetadata,
     ) -> String {
         let indent = "    ";
         let mut code = format!("{0}def to_rs(self):\n", indent);
         code.push_str(&format!(
             "{0}{0}return {1}.{2}(\n",
-            indent, package_name, struct_name
+            indent,
+            pyo3_module,
+            meta.name()
         ));
-        for (arg_name, arg_ty) in ctor.args.iter() {
-            let to_rs_field =
-                field_generator.generate_to_pyo3(&format!("self.{}", arg_name), arg_ty);
-            code.push_str(&format!(
-                "{0}{0}{0}{1}={2},\n",
-                indent, arg_name, to_rs_field
-            ));
+        for arg in ctor.args.iter() {
+            let attrs = meta.field_attrs(&arg.name);
+            if attrs.skip {
+                continue;
+            }
+            let field_name = format!("self.{}", field_generator.field_attribute(&arg.name, attrs));
+            let to_rs_arg = match arg.kind {
+                ArgKind::VarArgs => {
+                    format!(
+                        "*{}",
+                        field_generator.generate_to_pyo3(&field_name, &arg.ty)
+                    )
+                }
+                ArgKind::VarKwargs => format!(
+                    "**{}",
+                    field_generator.generate_to_pyo3(&field_name, option_inner_type(&arg.ty))
+                ),
+                // Arguments before `*args` can't be passed by keyword
+                ArgKind::PositionalOnly => field_generator.generate_to_pyo3(&field_name, &arg.ty),
+                ArgKind::Regular if ctor.has_var_args() => {
+                    field_generator.generate_to_pyo3(&field_name, &arg.ty)
+                }
+                _ => format!(
+                    "{}={}",
+                    arg.name,
+                    field_generator.generate_to_pyo3(&field_name, &arg.ty)
+                ),
+            };
+            code.push_str(&format!("{0}{0}{0}{1},\n", indent, to_rs_arg));
+        }
+        code.push_str(&format!("{0}{0})", indent));
+
+        code
+    }
+
+    fn generate_from_pyo3(
+        &self,
+        field_generator: &FieldGenerator,
+        meta: &StructMetadata,
+    ) -> String {
+        let indent = "    ";
+        let mut code = format!("{0}@classmethod\n{0}def from_rs(cls, obj):\n", indent);
+        code.push_str(&format!("{0}{0}return cls(\n", indent));
+
+        let struct_fields: Vec<(String, &syn::Type)> = meta
+            .fields
+            .iter()
+            .filter_map(|field| Some((field.ident.as_ref()?.to_string(), &field.ty)))
+            .collect();
+        let model_fields: Vec<&String> = match meta.constructor {
+            Some(ref ctor) => ctor.args.iter().map(|arg| &arg.name).collect(),
+            None => struct_fields.iter().map(|(ident, _)| ident).collect(),
+        };
+
+        for field_ident in model_fields {
+            let attrs = meta.field_attrs(field_ident);
+            if attrs.skip {
+                continue;
+            }
+            let field_name = field_generator.field_name(field_ident, attrs);
+            if let Some((_, field_ty)) = struct_fields.iter().find(|(i, _)| i == field_ident) {
+                let from_rs_field =
+                    field_generator.generate_from_pyo3(&format!("obj.{}", field_ident), field_ty);
+                code.push_str(&format!(
+                    "{0}{0}{0}{1}={2},\n",
+                    indent, field_name, from_rs_field
+                ));
+            } else {
+                code.push_str(&format!(
+                    "{0}{0}{0}# '{1}' has no matching field on the Rust struct\n",
+                    indent, field_name
+                ));
+            }
         }
         code.push_str(&format!("{0}{0})", indent));
 
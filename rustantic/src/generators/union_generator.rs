@@ -1,20 +1,20 @@
-use std::collections::HashSet;
-
-use itertools::{sorted, Itertools};
+use syn::Fields;
 
 use crate::{
     collector::MetadataCollector,
+    error::Error,
     models::{DiscriminatedUnionMetadata, ItemMetadata, UnionVariantMetadata},
 };
 
 use super::{
     field_generator::FieldGenerator,
     generator_base::{
-        GenerationResult, GeneratorConfig, PydanticCodeGenerator, PydanticCodeGeneratorFactory,
+        generate_model_config, GenerationResult, GeneratorConfig, PydanticCodeGenerator,
+        PydanticCodeGeneratorFactory,
     },
 };
 
-pub(crate) struct UnionCodeGenerator {}
+pub struct UnionCodeGenerator {}
 
 impl PydanticCodeGeneratorFactory for UnionCodeGenerator {
     fn create() -> Box<dyn PydanticCodeGenerator> {
@@ -32,56 +32,82 @@ impl PydanticCodeGenerator for UnionCodeGenerator {
         config: GeneratorConfig,
         collector: &MetadataCollector,
         meta: &ItemMetadata,
-    ) -> Result<String, ()> {
+    ) -> Result<GenerationResult, Error> {
         if let ItemMetadata::DiscriminatedUnion(union_md) = meta {
+            self.check_skipped_fields(union_md)?;
+            for variant in union_md.variants.iter() {
+                let idents: Vec<String> = variant
+                    .fields
+                    .iter()
+                    .filter_map(|field| Some(field.ident.as_ref()?.to_string()))
+                    .collect();
+                config.check_field_attributes(
+                    idents
+                        .iter()
+                        .map(|ident| (ident.as_str(), variant.field_attrs(ident))),
+                )?;
+            }
             Ok(self.generate_code(config, collector, union_md))
         } else {
-            Err(())
+            Err(Error::Generation(format!(
+                "'{}' is not an enum with data",
+                meta.path()
+            )))
         }
     }
 }
 
 impl UnionCodeGenerator {
+    /// Variant constructors take every field, `to_rs()` can't leave one out.
+    fn check_skipped_fields(&self, meta: &DiscriminatedUnionMetadata) -> Result<(), Error> {
+        for variant in meta.variants.iter() {
+            let skipped = variant
+                .fields
+                .iter()
+                .filter_map(|field| field.ident.as_ref())
+                .find(|ident| variant.field_attrs(&ident.to_string()).skip);
+            if let Some(ident) = skipped {
+                return Err(Error::Generation(format!(
+                    "`skip` on '{}' of variant '{}' leaves out an argument of the variant \
+                     constructor",
+                    ident, variant.ident
+                )));
+            }
+        }
+        Ok(())
+    }
+
     fn generate_code(
         &self,
         config: GeneratorConfig,
         collector: &MetadataCollector,
         meta: &DiscriminatedUnionMetadata,
-    ) -> String {
+    ) -> GenerationResult {
         let discriminator = self.generate_discriminator(meta);
-        let variants = self.generate_union_variants(config.clone(), collector, meta);
-        let definition = self.generate_type_definitions(&config, meta);
-        let imports = self.generate_import(config.package_name, &variants.additional_imports);
-        format!(
-            "{}\n{}\n\n{}\n{}\n{}",
-            config.header_comment, imports, discriminator, variants.code, definition
-        )
-    }
-
-    fn generate_import(&self, package_name: &str, additional_imports: &HashSet<String>) -> String {
-        let mut imports: HashSet<String> = vec![
+        let mut warnings = GenerationResult::default();
+        let has_from_rs = warnings.check_from_rs(collector.missing_from_rs(&meta.path()));
+        let mut result = self.generate_union_variants(config.clone(), collector, meta, has_from_rs);
+        let definition = self.generate_type_definitions(&config, meta, has_from_rs);
+        result.warnings.extend(warnings.warnings);
+        result.additional_imports.extend([
             "import enum".to_owned(),
             "from typing import Literal, Union, Any".to_owned(),
             "from pydantic import BaseModel, Field, RootModel".to_owned(),
-            format!("import {}", package_name),
-        ]
-        .into_iter()
-        .collect();
-
-        imports.extend(additional_imports.iter().map(|i| i.to_owned()));
-
-        sorted(imports).join("\n")
+            format!("import {}", config.pyo3_module(&meta.attrs)),
+        ]);
+        result.code = format!("{}\n{}\n{}", discriminator, result.code, definition);
+        result
     }
 
     fn generate_discriminator(&self, meta: &DiscriminatedUnionMetadata) -> String {
         let mut code = format!(
             "class {}(enum.Enum):\n",
-            self.generate_discriminator_name(&meta.ident)
+            self.generate_discriminator_name(meta.name())
         );
         for variant in meta.variants.iter() {
             code.push_str(&format!("    {} = enum.auto()\n", &variant.ident));
         }
-        code.push_str("\n");
+        code.push('\n');
         code
     }
 
@@ -90,15 +116,16 @@ impl UnionCodeGenerator {
         config: GeneratorConfig,
         collector: &MetadataCollector,
         meta: &DiscriminatedUnionMetadata,
+        has_from_rs: bool,
     ) -> GenerationResult {
-        let field_generator = FieldGenerator::new(config.clone(), collector.entities());
+        let path = meta.path();
+        let field_generator = FieldGenerator::new(config.clone(), collector, &path);
         let mut variants = vec![];
         let mut result = GenerationResult::default();
         for variant in meta.variants.iter() {
-            let variant_code = self.generate_union_variant(&field_generator, meta, variant);
-            result
-                .additional_imports
-                .extend(variant_code.additional_imports);
+            let variant_code =
+                self.generate_union_variant(&config, &field_generator, meta, variant, has_from_rs);
+            result.extend_imports(&variant_code);
             variants.push(variant_code.code);
         }
         result.code = variants.join("\n");
@@ -107,80 +134,179 @@ impl UnionCodeGenerator {
 
     fn generate_union_variant(
         &self,
+        config: &GeneratorConfig,
         field_generator: &FieldGenerator,
         meta: &DiscriminatedUnionMetadata,
         variant: &UnionVariantMetadata,
+        has_from_rs: bool,
     ) -> GenerationResult {
         let mut result = GenerationResult::default();
-        let field_gen = field_generator.generate(
-            "value",
-            variant.ty.as_ref().expect("Named enum not supported"),
-        );
-        let code = vec![
-            format!("class {0}{1}(BaseModel):", &meta.ident, &variant.ident),
+        let mut code = vec![format!("class {}(BaseModel):", meta.variant_name(variant))];
+        if let Some(model_config) = generate_model_config(&meta.attrs, false) {
+            code.push(model_config.code.trim_end().to_owned());
+            result.extend_imports(&model_config);
+        }
+        code.push(format!(
+            "    kind: Literal[{0}.{1}] = Field(default={0}.{1}, init=False, frozen=True)",
+            self.generate_discriminator_name(meta.name()),
+            &variant.ident
+        ));
+
+        let mut ctor_args = vec![];
+        let mut from_rs_args = vec![];
+        match variant.fields {
+            Fields::Named(ref named_fields) => {
+                for field in named_fields.named.iter() {
+                    let field_ident = field
+                        .ident
+                        .as_ref()
+                        .expect("Identifier cannot be empty for named fields")
+                        .to_string();
+                    let attrs = variant.field_attrs(&field_ident);
+                    let field_gen = field_generator.generate(&field_ident, &field.ty, attrs);
+                    code.push(format!("    {}", field_gen.code));
+                    result.extend_imports(&field_gen);
+
+                    let field_name = field_generator.field_name(&field_ident, attrs);
+                    let field_attribute = field_generator.field_attribute(&field_ident, attrs);
+                    ctor_args.push(format!(
+                        "{}={}",
+                        field_ident,
+                        field_generator
+                            .generate_to_pyo3(&format!("self.{}", field_attribute), &field.ty)
+                    ));
+                    from_rs_args.push(format!(
+                        "{}={}",
+                        field_name,
+                        field_generator
+                            .generate_from_pyo3(&format!("obj.{}", field_ident), &field.ty)
+                    ));
+                }
+            }
+            Fields::Unnamed(ref unnamed_fields) => {
+                let single_field = unnamed_fields.unnamed.len() == 1;
+                for (idx, field) in unnamed_fields.unnamed.iter().enumerate() {
+                    let attrs = variant.field_attrs(&idx.to_string());
+                    let default_name = if single_field {
+                        "value".to_owned()
+                    } else {
+                        format!("field_{}", idx)
+                    };
+                    let field_name = field_generator.field_name(&default_name, attrs);
+                    let field_attribute = field_generator.field_attribute(&default_name, attrs);
+                    let field_gen = field_generator.generate(&default_name, &field.ty, attrs);
+                    code.push(format!("    {}", field_gen.code));
+                    result.extend_imports(&field_gen);
+                    ctor_args.push(
+                        field_generator
+                            .generate_to_pyo3(&format!("self.{}", field_attribute), &field.ty),
+                    );
+                    from_rs_args.push(format!(
+                        "{}={}",
+                        field_name,
+                        field_generator.generate_from_pyo3(&format!("obj._{}", idx), &field.ty)
+                    ));
+                }
+            }
+            Fields::Unit => {}
+        }
+
+        code.extend([
+            "".to_owned(),
+            "    def to_rs(self):".to_owned(),
             format!(
-                "    kind: Literal[{0}.{1}] = Field(default={0}.{1}, init=False, frozen=True)",
-                self.generate_discriminator_name(&meta.ident),
-                &variant.ident
+                "        return {}.{}.{}({})",
+                config.pyo3_module(&meta.attrs),
+                meta.name(),
+                &variant.ident,
+                ctor_args.join(", ")
             ),
-            format!("    {}", field_gen.code),
-            "\n".to_string(),
-        ];
+        ]);
+        if has_from_rs {
+            code.extend([
+                "".to_owned(),
+                "    @classmethod".to_owned(),
+                "    def from_rs(cls, obj):".to_owned(),
+                format!("        return cls({})", from_rs_args.join(", ")),
+            ]);
+        }
+        code.push("\n".to_owned());
         result.code = code.join("\n");
         result
-            .additional_imports
-            .extend(field_gen.additional_imports);
-        result
     }
 
     fn generate_type_definitions(
         &self,
         config: &GeneratorConfig,
         meta: &DiscriminatedUnionMetadata,
+        has_from_rs: bool,
     ) -> String {
         let variants: Vec<String> = meta
             .variants
             .iter()
-            .map(|v| format!("{}{}", &meta.ident, &v.ident))
+            .map(|variant| meta.variant_name(variant))
             .collect();
 
-        vec![
-            format!("{}Type = Union[{}]", &meta.ident, variants.join(",")),
-            format!("\nclass {0}(RootModel[{0}Type]):", &meta.ident),
+        let mut code = vec![
+            format!("{}Type = Union[{}]", meta.name(), variants.join(",")),
+            format!("\nclass {0}(RootModel[{0}Type]):", meta.name()),
             format!(
                 "    root: {}Type = Field(..., discriminator=\"kind\")",
-                &meta.ident
+                meta.name()
             ),
-            self.generate_to_pyo3(config, meta),
-        ]
-        .join("\n")
+            self.generate_to_pyo3(),
+        ];
+        if has_from_rs {
+            code.push(self.generate_from_pyo3(config, meta));
+        }
+        code.join("\n")
     }
 
     fn generate_discriminator_name(&self, ident: &str) -> String {
         format!("{}Discriminator", ident)
     }
 
-    fn generate_to_pyo3(
+    fn generate_to_pyo3(&self) -> String {
+        ["    def to_rs(self):", "        return self.root.to_rs()\n"].join("\n")
+    }
+
+    fn generate_from_pyo3(
         &self,
         config: &GeneratorConfig,
         meta: &DiscriminatedUnionMetadata,
     ) -> String {
         let mut code_sections = vec![
-            "    def to_rs(self):".to_owned(),
-            "        inner_to_rs = getattr(self.root.value, \"to_rs\", lambda v: v)".to_string(),
-            "        val: Any = inner_to_rs(self.root.value)".to_string(),
-            "        match self.root.kind:".to_string(),
+            "    @classmethod".to_owned(),
+            "    def from_rs(cls, obj):".to_owned(),
         ];
+        let has_match = config.python_version.has_match();
+        if has_match {
+            code_sections.push("        match obj:".to_owned());
+        }
         for variant in meta.variants.iter() {
-            code_sections.push(format!(
-                "            case {}.{}:",
-                self.generate_discriminator_name(&meta.ident),
+            let variant_class = format!(
+                "{}.{}.{}",
+                config.pyo3_module(&meta.attrs),
+                meta.name(),
                 &variant.ident
-            ));
-            code_sections.push(format!(
-                "                return {}.{}.{}(val)\n",
-                config.package_name, &meta.ident, &variant.ident,
-            ));
+            );
+            let from_rs = format!("return cls({}.from_rs(obj))", meta.variant_name(variant));
+            if has_match {
+                code_sections.push(format!("            case {}():", variant_class));
+                code_sections.push(format!("                {}", from_rs));
+            } else {
+                code_sections.push(format!("        if isinstance(obj, {}):", variant_class));
+                code_sections.push(format!("            {}", from_rs));
+            }
+        }
+        if has_match {
+            code_sections.push("            case _:".to_owned());
+            code_sections.push(
+                "                raise ValueError(f\"Unsupported value '{obj}'\")\n".to_owned(),
+            );
+        } else {
+            code_sections
+                .push("        raise ValueError(f\"Unsupported value '{obj}'\")\n".to_owned());
         }
 
         code_sections.join("\n")
@@ -1,7 +1,37 @@
 #[cfg(feature = "generator")]
+mod builder;
+#[cfg(feature = "generator")]
 pub mod collector;
 #[cfg(feature = "generator")]
+mod config_file;
+#[cfg(feature = "generator")]
+mod diagnostic;
+#[cfg(feature = "generator")]
+mod error;
+#[cfg(feature = "generator")]
 pub mod generators;
+#[cfg(feature = "generator")]
+mod module_scope;
+#[cfg(feature = "generator")]
+mod signature;
+#[cfg(feature = "generator")]
+mod type_mapping;
 
+pub mod attributes;
 pub mod marcos;
 pub mod models;
+
+#[cfg(feature = "generator")]
+pub use builder::{
+    Backend, F32Precision, Naming, PythonVersion, Rustantic, RustanticBuilder, Verbosity,
+};
+#[cfg(feature = "generator")]
+pub use diagnostic::{Diagnostic, Severity};
+#[cfg(feature = "generator")]
+pub use error::Error;
+#[cfg(feature = "generator")]
+pub use generators::{
+    GenerationResult, GeneratorConfig, Layout, PydanticCodeGenerator, PydanticCodeGeneratorFactory,
+};
+#[cfg(feature = "generator")]
+pub use type_mapping::TypeMapping;
@@ -1,5 +1,32 @@
-use syn::Attribute;
+use syn::{punctuated::Punctuated, Attribute, Field, Meta, Token, Type};
 
 pub fn has_rustantic_attr(attrs: &[Attribute]) -> bool {
     attrs.iter().any(|attr| attr.path().is_ident("pydantic"))
 }
+
+/// Whether the field has a pyo3 getter, `#[pyo3(get)]` or `#[pyo3(get, set)]`.
+pub fn has_pyo3_getter(field: &Field) -> bool {
+    field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("pyo3"))
+        .any(|attr| {
+            attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+                .map(|args| args.iter().any(|arg| arg.path().is_ident("get")))
+                .unwrap_or(false)
+        })
+}
+
+pub fn is_option_type(ty: &Type) -> bool {
+    if let Type::Path(type_path) = ty {
+        type_path.qself.is_none()
+            && type_path
+                .path
+                .segments
+                .last()
+                .map(|seg| seg.ident == "Option")
+                .unwrap_or(false)
+    } else {
+        false
+    }
+}
@@ -1,45 +1,209 @@
-use syn::{Fields, FieldsNamed, Type};
+use std::collections::HashMap;
+
+use syn::{Expr, Field, Fields, Type};
+
+use crate::attributes::{ContainerAttributes, FieldAttributes};
+use crate::marcos::has_pyo3_getter;
+
+static DEFAULT_FIELD_ATTRS: FieldAttributes = FieldAttributes {
+    rename: None,
+    skip: false,
+    description: None,
+    constraints: Vec::new(),
+};
 
 pub enum ItemKind {
     Request,
     Response,
 }
 
+/// How a constructor argument is passed, from the pyo3 `signature`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ArgKind {
+    /// Declared before `/`.
+    PositionalOnly,
+    Regular,
+    /// Declared after `*` or `*args`.
+    KeywordOnly,
+    /// `*args`
+    VarArgs,
+    /// `**kwargs`
+    VarKwargs,
+}
+
+#[derive(Clone)]
+pub struct ConstructorArg {
+    pub name: String,
+    pub ty: Type,
+    pub kind: ArgKind,
+    /// Rust expression of the default value in the pyo3 `signature`.
+    pub default: Option<Expr>,
+}
+
 #[derive(Clone)]
 pub struct ConstructorMetadata {
-    pub args: Vec<(String, Type)>,
+    pub args: Vec<ConstructorArg>,
+}
+
+impl ConstructorMetadata {
+    pub fn has_var_args(&self) -> bool {
+        self.args.iter().any(|arg| arg.kind == ArgKind::VarArgs)
+    }
 }
 
 #[derive(Clone)]
 pub struct StructMetadata {
     pub ident: String,
+    /// Rust module path of the item, e.g. `crate::models`.
+    pub module: String,
+    pub attrs: ContainerAttributes,
     pub constructor: Option<ConstructorMetadata>,
     pub fields: Fields,
+    /// Field level `#[pydantic(...)]` arguments keyed by field ident, or index for tuple fields.
+    pub field_attrs: HashMap<String, FieldAttributes>,
 }
 
 impl StructMetadata {
+    pub fn name(&self) -> &str {
+        self.attrs.name.as_deref().unwrap_or(&self.ident)
+    }
+
+    pub fn path(&self) -> String {
+        format!("{}::{}", self.module, self.ident)
+    }
+
+    pub fn field_attrs(&self, ident: &str) -> &FieldAttributes {
+        self.field_attrs.get(ident).unwrap_or(&DEFAULT_FIELD_ATTRS)
+    }
+
     pub fn set_ctor(&mut self, ctor: ConstructorMetadata) {
         self.constructor = Some(ctor);
     }
+
+    /// Fields read by `from_rs()`, by their Python attribute name.
+    fn read_fields(&self) -> Vec<(String, &Field)> {
+        match self.fields {
+            Fields::Named(_) => self
+                .fields
+                .iter()
+                .filter_map(|field| {
+                    let ident = field.ident.as_ref()?.to_string();
+                    let is_model_field = match self.constructor {
+                        Some(ref ctor) => ctor.args.iter().any(|arg| arg.name == ident),
+                        None => true,
+                    };
+                    (is_model_field && !self.field_attrs(&ident).skip).then_some((ident, field))
+                })
+                .collect(),
+            _ => self
+                .fields
+                .iter()
+                .enumerate()
+                .map(|(idx, field)| (format!("_{}", idx), field))
+                .collect(),
+        }
+    }
+
+    /// Fields `from_rs()` reads without a pyo3 getter, see `#[pydantic(get_all)]`.
+    pub fn fields_without_getter(&self) -> Vec<String> {
+        if self.attrs.get_all {
+            return vec![];
+        }
+        self.read_fields()
+            .into_iter()
+            .filter(|(_, field)| !has_pyo3_getter(field))
+            .map(|(name, _)| name)
+            .collect()
+    }
+
+    /// Whether the struct exposes its fields to `from_rs()`, it is left out of the models
+    /// without a getter.
+    pub fn has_getters(&self) -> bool {
+        let read_fields = self.read_fields();
+        read_fields.is_empty()
+            || self.attrs.get_all
+            || read_fields.iter().any(|(_, field)| has_pyo3_getter(field))
+    }
+
+    /// Required `#[new]` arguments without a field of the same name, `from_rs()` can't read
+    /// them back. Arguments with a default are left to it, tuple structs are read by position.
+    pub fn unreadable_args(&self) -> Vec<&str> {
+        let (Some(ref ctor), Fields::Named(_)) = (&self.constructor, &self.fields) else {
+            return vec![];
+        };
+        ctor.args
+            .iter()
+            .filter(|arg| {
+                arg.default.is_none() && !matches!(arg.kind, ArgKind::VarArgs | ArgKind::VarKwargs)
+            })
+            .filter(|arg| {
+                !self
+                    .fields
+                    .iter()
+                    .any(|field| field.ident.as_ref().is_some_and(|ident| *ident == arg.name))
+            })
+            .map(|arg| arg.name.as_str())
+            .collect()
+    }
 }
 
 pub struct UnitEnumMetadata {
     pub ident: String,
+    pub module: String,
+    pub attrs: ContainerAttributes,
     pub variants: Vec<(String, Option<String>)>,
 }
 
+impl UnitEnumMetadata {
+    pub fn name(&self) -> &str {
+        self.attrs.name.as_deref().unwrap_or(&self.ident)
+    }
+}
+
 pub struct UnionVariantMetadata {
     pub ident: String,
-    pub ty: Option<Type>,
-    #[allow(unused)]
-    pub named_fields: Option<FieldsNamed>,
+    pub fields: Fields,
+    /// Field level `#[pydantic(...)]` arguments keyed by field ident, or index for tuple fields.
+    pub field_attrs: HashMap<String, FieldAttributes>,
+}
+
+impl UnionVariantMetadata {
+    pub fn field_attrs(&self, ident: &str) -> &FieldAttributes {
+        self.field_attrs.get(ident).unwrap_or(&DEFAULT_FIELD_ATTRS)
+    }
 }
 
 pub struct DiscriminatedUnionMetadata {
     pub ident: String,
+    pub module: String,
+    pub attrs: ContainerAttributes,
     pub variants: Vec<UnionVariantMetadata>,
 }
 
+impl DiscriminatedUnionMetadata {
+    pub fn name(&self) -> &str {
+        self.attrs.name.as_deref().unwrap_or(&self.ident)
+    }
+
+    pub fn path(&self) -> String {
+        format!("{}::{}", self.module, self.ident)
+    }
+
+    /// Name of the model of `variant`, e.g. `ShapeCircle` for `Shape::Circle`.
+    pub fn variant_name(&self, variant: &UnionVariantMetadata) -> String {
+        format!("{}{}", self.name(), variant.ident)
+    }
+}
+
+/// Why a model has no `from_rs()`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MissingFromRs {
+    /// A struct without getters, `from_rs()` is opted in with `#[pydantic(get_all)]`.
+    NoGetters,
+    /// Reported as a warning.
+    Unreadable(String),
+}
+
 pub enum ItemMetadata {
     Struct(StructMetadata),
     UnitEnum(UnitEnumMetadata),
@@ -54,4 +218,71 @@ impl ItemMetadata {
             ItemMetadata::DiscriminatedUnion(union_md) => &union_md.ident,
         }
     }
+
+    pub fn module(&self) -> &str {
+        match self {
+            ItemMetadata::Struct(struct_md) => &struct_md.module,
+            ItemMetadata::UnitEnum(enum_md) => &enum_md.module,
+            ItemMetadata::DiscriminatedUnion(union_md) => &union_md.module,
+        }
+    }
+
+    /// Full Rust path of the item, e.g. `crate::models::User`, identifies the item.
+    pub fn path(&self) -> String {
+        format!("{}::{}", self.module(), self.ident())
+    }
+
+    pub fn attrs(&self) -> &ContainerAttributes {
+        match self {
+            ItemMetadata::Struct(struct_md) => &struct_md.attrs,
+            ItemMetadata::UnitEnum(enum_md) => &enum_md.attrs,
+            ItemMetadata::DiscriminatedUnion(union_md) => &union_md.attrs,
+        }
+    }
+
+    /// Types of every field, constructor argument and variant field of the item.
+    pub fn field_types(&self) -> Vec<&Type> {
+        match self {
+            ItemMetadata::Struct(struct_md) => struct_md
+                .fields
+                .iter()
+                .map(|field| &field.ty)
+                .chain(
+                    struct_md
+                        .constructor
+                        .iter()
+                        .flat_map(|ctor| ctor.args.iter().map(|arg| &arg.ty)),
+                )
+                .collect(),
+            ItemMetadata::UnitEnum(_) => vec![],
+            ItemMetadata::DiscriminatedUnion(union_md) => union_md
+                .variants
+                .iter()
+                .flat_map(|variant| variant.fields.iter().map(|field| &field.ty))
+                .collect(),
+        }
+    }
+
+    /// Python name of the item, `#[pydantic(name = "...")]` or the Rust ident.
+    pub fn name(&self) -> &str {
+        match self {
+            ItemMetadata::Struct(struct_md) => struct_md.name(),
+            ItemMetadata::UnitEnum(enum_md) => enum_md.name(),
+            ItemMetadata::DiscriminatedUnion(union_md) => union_md.name(),
+        }
+    }
+
+    /// Python names of the models generated for the item, the union variants have their own.
+    pub fn model_names(&self) -> Vec<String> {
+        let mut names = vec![self.name().to_owned()];
+        if let ItemMetadata::DiscriminatedUnion(union_md) = self {
+            names.extend(
+                union_md
+                    .variants
+                    .iter()
+                    .map(|variant| union_md.variant_name(variant)),
+            );
+        }
+        names
+    }
 }
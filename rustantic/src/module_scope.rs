@@ -0,0 +1,173 @@
+use std::collections::HashMap;
+
+use syn::UseTree;
+
+/// Names brought in scope by the `use` declarations of a module.
+#[derive(Default)]
+struct Scope {
+    /// Imported name or alias to the candidate paths it refers to.
+    imports: HashMap<String, Vec<Vec<String>>>,
+    /// Candidate paths of the `use path::*` declarations.
+    globs: Vec<Vec<String>>,
+}
+
+/// Resolves Rust paths written in a module to the full path of an item, following
+/// `use` declarations, `self`/`super`/`crate` prefixes and re-exports.
+#[derive(Default)]
+pub(crate) struct ModuleScopes {
+    scopes: HashMap<String, Scope>,
+}
+
+/// Re-exports followed before giving up, guards against `use` cycles.
+const MAX_REEXPORT_DEPTH: usize = 8;
+
+impl ModuleScopes {
+    pub fn add_use(&mut self, module: &str, tree: &UseTree) {
+        self.add_use_tree(module, vec![], tree);
+    }
+
+    fn add_use_tree(&mut self, module: &str, mut prefix: Vec<String>, tree: &UseTree) {
+        match tree {
+            UseTree::Path(use_path) => {
+                prefix.push(use_path.ident.to_string());
+                self.add_use_tree(module, prefix, &use_path.tree);
+            }
+            UseTree::Name(use_name) => {
+                let name = use_name.ident.to_string();
+                // `use a::b::{self}` imports the module `b`
+                let (alias, path) = if name == "self" {
+                    (prefix.last().cloned().unwrap_or_default(), prefix)
+                } else {
+                    prefix.push(name.clone());
+                    (name, prefix)
+                };
+                self.import(module, alias, path);
+            }
+            UseTree::Rename(use_rename) => {
+                prefix.push(use_rename.ident.to_string());
+                self.import(module, use_rename.rename.to_string(), prefix);
+            }
+            UseTree::Glob(_) => {
+                let candidates = absolute_candidates(module, &prefix);
+                self.scopes
+                    .entry(module.to_owned())
+                    .or_default()
+                    .globs
+                    .extend(candidates);
+            }
+            UseTree::Group(group) => {
+                for item in group.items.iter() {
+                    self.add_use_tree(module, prefix.clone(), item);
+                }
+            }
+        }
+    }
+
+    fn import(&mut self, module: &str, alias: String, path: Vec<String>) {
+        let candidates = absolute_candidates(module, &path);
+        self.scopes
+            .entry(module.to_owned())
+            .or_default()
+            .imports
+            .entry(alias)
+            .or_default()
+            .extend(candidates);
+    }
+
+    /// Full path of the item `segments` refers to in `module`, if `is_item` accepts one.
+    pub fn resolve(
+        &self,
+        module: &str,
+        segments: &[String],
+        is_item: &dyn Fn(&str) -> bool,
+    ) -> Option<String> {
+        let (first, rest) = segments.split_first()?;
+        let imported = self
+            .scopes
+            .get(module)
+            .and_then(|scope| scope.imports.get(first));
+
+        let candidates: Vec<Vec<String>> = match (first.as_str(), imported) {
+            ("crate" | "self" | "super", _) => absolute_candidates(module, segments),
+            // An imported name shadows the items of the module
+            (_, Some(targets)) => targets
+                .iter()
+                .map(|target| target.iter().chain(rest).cloned().collect())
+                .collect(),
+            _ => {
+                let mut candidates = absolute_candidates(module, segments);
+                if rest.is_empty() {
+                    if let Some(scope) = self.scopes.get(module) {
+                        candidates.extend(
+                            scope
+                                .globs
+                                .iter()
+                                .map(|glob| glob.iter().chain([first]).cloned().collect()),
+                        );
+                    }
+                }
+                candidates
+            }
+        };
+
+        candidates
+            .into_iter()
+            .find_map(|candidate| self.resolve_absolute(&candidate, is_item, MAX_REEXPORT_DEPTH))
+    }
+
+    fn resolve_absolute(
+        &self,
+        path: &[String],
+        is_item: &dyn Fn(&str) -> bool,
+        depth: usize,
+    ) -> Option<String> {
+        let full_path = path.join("::");
+        if is_item(&full_path) {
+            return Some(full_path);
+        }
+        let (name, module) = path.split_last()?;
+        if depth == 0 || module.is_empty() {
+            return None;
+        }
+
+        // The item may be re-exported by a `use` of the module
+        let scope = self.scopes.get(&module.join("::"))?;
+        let reexports = scope.imports.get(name).into_iter().flatten().cloned();
+        let globs = scope
+            .globs
+            .iter()
+            .map(|glob| glob.iter().chain([name]).cloned().collect());
+        reexports.chain(globs).find_map(|candidate: Vec<String>| {
+            self.resolve_absolute(&candidate, is_item, depth - 1)
+        })
+    }
+}
+
+/// Absolute paths `segments` may refer to in `module`. Paths without a `crate`, `self`
+/// or `super` prefix are relative to the module or, 2015 edition style, to the crate root.
+fn absolute_candidates(module: &str, segments: &[String]) -> Vec<Vec<String>> {
+    let mut current: Vec<String> = module.split("::").map(str::to_owned).collect();
+    let mut rest = segments;
+    match rest.first().map(String::as_str) {
+        Some("crate") => return vec![segments.to_vec()],
+        Some("self") => rest = &rest[1..],
+        Some("super") => {
+            while rest.first().map(String::as_str) == Some("super") {
+                if current.len() > 1 {
+                    current.pop();
+                }
+                rest = &rest[1..];
+            }
+        }
+        _ => {
+            let relative = current.iter().chain(rest).cloned().collect();
+            let from_root = ["crate".to_owned()].iter().chain(rest).cloned().collect();
+            return if current.len() == 1 {
+                vec![relative]
+            } else {
+                vec![relative, from_root]
+            };
+        }
+    }
+    vec![current.into_iter().chain(rest.iter().cloned()).collect()]
+}
@@ -0,0 +1,86 @@
+use syn::{parse::ParseStream, Attribute, Expr, Ident, Token};
+
+/// Item of a pyo3 `#[pyo3(signature = (...))]` attribute.
+pub(crate) enum SignatureItem {
+    /// `/`
+    PositionalOnlyMarker,
+    /// `*`
+    KeywordOnlyMarker,
+    /// `name` or `name = default`
+    Arg(Ident, Option<Box<Expr>>),
+    /// `*args`
+    VarArgs(Ident),
+    /// `**kwargs`
+    VarKwargs(Ident),
+}
+
+/// Finds and parses the `signature` option in the `#[pyo3(...)]` attributes of a function.
+pub(crate) fn parse_pyo3_signature(attrs: &[Attribute]) -> syn::Result<Option<Vec<SignatureItem>>> {
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("pyo3")) {
+        if let Some(signature) = attr.parse_args_with(parse_pyo3_options)? {
+            return Ok(Some(signature));
+        }
+    }
+    Ok(None)
+}
+
+fn parse_pyo3_options(input: ParseStream) -> syn::Result<Option<Vec<SignatureItem>>> {
+    let mut signature = None;
+    while !input.is_empty() {
+        let key: Ident = input.parse()?;
+        if key == "signature" {
+            input.parse::<Token![=]>()?;
+            let content;
+            syn::parenthesized!(content in input);
+            signature = Some(parse_signature_items(&content)?);
+        } else {
+            // Other pyo3 options (e.g. `text_signature`) are skipped
+            while !input.is_empty() && !input.peek(Token![,]) {
+                input.parse::<proc_macro2::TokenTree>()?;
+            }
+        }
+
+        if input.is_empty() {
+            break;
+        }
+        input.parse::<Token![,]>()?;
+    }
+    Ok(signature)
+}
+
+fn parse_signature_items(input: ParseStream) -> syn::Result<Vec<SignatureItem>> {
+    let mut items = vec![];
+    while !input.is_empty() {
+        let item = if input.peek(Token![/]) {
+            input.parse::<Token![/]>()?;
+            SignatureItem::PositionalOnlyMarker
+        } else if input.peek(Token![*]) && input.peek2(Token![*]) {
+            input.parse::<Token![*]>()?;
+            input.parse::<Token![*]>()?;
+            SignatureItem::VarKwargs(input.parse()?)
+        } else if input.peek(Token![*]) {
+            input.parse::<Token![*]>()?;
+            if input.peek(Ident) {
+                SignatureItem::VarArgs(input.parse()?)
+            } else {
+                SignatureItem::KeywordOnlyMarker
+            }
+        } else {
+            let ident: Ident = input.parse()?;
+            let default = if input.peek(Token![=]) {
+                input.parse::<Token![=]>()?;
+                Some(Box::new(input.parse::<Expr>()?))
+            } else {
+                None
+            };
+            SignatureItem::Arg(ident, default)
+        };
+        items.push(item);
+
+        if input.is_empty() {
+            break;
+        }
+        input.parse::<Token![,]>()?;
+    }
+    Ok(items)
+}
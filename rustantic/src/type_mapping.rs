@@ -0,0 +1,92 @@
+use std::collections::BTreeMap;
+
+use serde::{de::Error as _, Deserialize, Deserializer};
+
+use crate::attributes::ConstraintValue;
+
+/// Python type a Rust type is generated as, for types rustantic doesn't know, e.g.
+/// `rust_decimal::Decimal` as `decimal.Decimal`.
+///
+/// The conversions are Python expressions where `{value}` is replaced by the converted value,
+/// without one the value is passed as is.
+///
+/// ```
+/// use rustantic::TypeMapping;
+///
+/// let money = TypeMapping::new("billing.Money")
+///     .import("import billing")
+///     .to_rs("{value}.to_rs()")
+///     .from_rs("billing.Money.from_rs({value})");
+/// ```
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TypeMapping {
+    pub(crate) python_type: String,
+    #[serde(default)]
+    pub(crate) imports: Vec<String>,
+    #[serde(default, deserialize_with = "deserialize_constraints")]
+    pub(crate) constraints: Vec<(String, ConstraintValue)>,
+    pub(crate) to_rs: Option<String>,
+    pub(crate) from_rs: Option<String>,
+}
+
+impl TypeMapping {
+    pub fn new(python_type: impl Into<String>) -> Self {
+        Self {
+            python_type: python_type.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Import line the Python type needs, e.g. `import decimal`.
+    pub fn import(mut self, line: impl Into<String>) -> Self {
+        self.imports.push(line.into());
+        self
+    }
+
+    /// `Field(...)` argument of the fields of this type, the field attributes take precedence.
+    pub fn constraint(mut self, key: impl Into<String>, value: ConstraintValue) -> Self {
+        self.constraints.push((key.into(), value));
+        self
+    }
+
+    /// Converts the Python value to the value passed to the pyclass.
+    pub fn to_rs(mut self, expr: impl Into<String>) -> Self {
+        self.to_rs = Some(expr.into());
+        self
+    }
+
+    /// Converts the pyclass value to the Python value.
+    pub fn from_rs(mut self, expr: impl Into<String>) -> Self {
+        self.from_rs = Some(expr.into());
+        self
+    }
+}
+
+/// Constraints are a table, strings become Python strings and other values are copied.
+fn deserialize_constraints<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<(String, ConstraintValue)>, D::Error> {
+    let table = BTreeMap::<String, toml::Value>::deserialize(deserializer)?;
+    table
+        .into_iter()
+        .map(|(key, value)| {
+            let value = match value {
+                toml::Value::String(string) => ConstraintValue::Str(string),
+                toml::Value::Integer(number) => ConstraintValue::Number(number.to_string()),
+                toml::Value::Float(number) if number.is_finite() => {
+                    ConstraintValue::Number(format!("{:?}", number))
+                }
+                toml::Value::Boolean(true) => ConstraintValue::Number("True".to_owned()),
+                toml::Value::Boolean(false) => ConstraintValue::Number("False".to_owned()),
+                other => {
+                    return Err(D::Error::custom(format!(
+                        "unsupported value `{}` for constraint `{}`",
+                        other, key
+                    )))
+                }
+            };
+            Ok((key, value))
+        })
+        .collect()
+}
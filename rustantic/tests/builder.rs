@@ -0,0 +1,78 @@
+use std::fs;
+use std::path::Path;
+
+use rustantic::{Error, PythonVersion, Rustantic, TypeMapping};
+
+fn fixture() -> &'static Path {
+    Path::new(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/models"
+    ))
+}
+
+#[test]
+fn rejects_invalid_options() {
+    let invalid_package = Rustantic::builder()
+        .crate_dir(fixture())
+        .package_name("my-package")
+        .build();
+    assert!(matches!(invalid_package, Err(Error::Config(_))));
+
+    let old_python = Rustantic::builder()
+        .crate_dir(fixture())
+        .package_name("models")
+        .python_version(PythonVersion::new(3, 8))
+        .build();
+    assert!(matches!(old_python, Err(Error::Config(_))));
+
+    let invalid_mapping = Rustantic::builder()
+        .crate_dir(fixture())
+        .package_name("models")
+        .type_mapping("Vec<u8>", TypeMapping::new("bytes"))
+        .build();
+    assert!(matches!(invalid_mapping, Err(Error::Config(_))));
+}
+
+#[test]
+fn defaults_follow_the_package_name() {
+    let rustantic = Rustantic::builder()
+        .crate_dir(fixture())
+        .package_name("models")
+        .build()
+        .unwrap();
+    assert_eq!(rustantic.models_package_name(), "models.generated");
+    assert_eq!(rustantic.output_dir(), fixture().join("models/generated"));
+}
+
+#[test]
+fn missing_sources_are_an_error() {
+    let missing = Path::new(env!("CARGO_TARGET_TMPDIR")).join("missing_crate");
+    let result = Rustantic::builder()
+        .crate_dir(&missing)
+        .package_name("models")
+        .output_dir(missing.join("generated"))
+        .generate();
+    assert!(matches!(result, Err(Error::Io { .. })), "{:?}", result);
+}
+
+#[test]
+fn invalid_config_file_is_an_error() {
+    let crate_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("invalid_config_file");
+    fs::create_dir_all(&crate_dir).unwrap();
+    fs::write(
+        crate_dir.join("rustantic.toml"),
+        "[types.Decimal]\npython_type = \"decimal.Decimal\"\nimport = \"decimal\"\n",
+    )
+    .unwrap();
+    let result = Rustantic::builder()
+        .crate_dir(&crate_dir)
+        .package_name("models")
+        .build();
+    match result {
+        Err(Error::Config(message)) => assert!(message.contains("import"), "{}", message),
+        other => panic!(
+            "expected a configuration error, got {:?}",
+            other.map(|_| ())
+        ),
+    }
+}
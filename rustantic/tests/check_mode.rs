@@ -0,0 +1,116 @@
+use std::fs;
+use std::path::Path;
+
+use rustantic::{Error, Layout, Rustantic, RustanticBuilder};
+
+fn fixture_builder(output_dir: &Path) -> RustanticBuilder {
+    Rustantic::builder()
+        .crate_dir(Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/models"))
+        .package_name("fixture_models")
+        .output_dir(output_dir)
+}
+
+#[test]
+fn check_reports_stale_missing_and_extra_files() {
+    let output_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("check_mode");
+    if output_dir.exists() {
+        fs::remove_dir_all(&output_dir).unwrap();
+    }
+    fixture_builder(&output_dir).generate().unwrap();
+    fixture_builder(&output_dir).check(true).generate().unwrap();
+
+    let item = output_dir.join("item.py");
+    let stale = fs::read_to_string(&item)
+        .unwrap()
+        .replace("qty", "quantity");
+    fs::write(&item, &stale).unwrap();
+    fs::remove_file(output_dir.join("color.py")).unwrap();
+    // Owned by a previous generation, unlike the unlisted files
+    fs::write(output_dir.join("removed.py"), "class Removed:\n    pass\n").unwrap();
+    fs::write(output_dir.join("custom.py"), "class Custom:\n    pass\n").unwrap();
+    fs::create_dir_all(output_dir.join("old/__pycache__")).unwrap();
+    fs::write(
+        output_dir.join("old/legacy.py"),
+        "class Legacy:\n    pass\n",
+    )
+    .unwrap();
+    fs::write(output_dir.join("old/__pycache__/legacy.py"), "").unwrap();
+    fs::write(output_dir.join("notes.txt"), "not a model\n").unwrap();
+    let manifest = output_dir.join(".rustantic-manifest");
+    let mut owned = fs::read_to_string(&manifest).unwrap();
+    owned.push_str("removed.py\n");
+    fs::write(&manifest, owned).unwrap();
+
+    let diff = match fixture_builder(&output_dir).check(true).generate() {
+        Err(Error::OutOfDate(diff)) => diff,
+        other => panic!("expected the models to be out of date, got {:?}", other),
+    };
+    assert!(diff.contains("--- a/item.py\n+++ b/item.py\n"), "{}", diff);
+    assert!(diff.contains("-    quantity: "), "{}", diff);
+    assert!(diff.contains("+    qty: "), "{}", diff);
+    assert!(diff.contains("--- /dev/null\n+++ b/color.py\n"), "{}", diff);
+    assert!(
+        diff.contains("--- a/removed.py\n+++ /dev/null\n"),
+        "{}",
+        diff
+    );
+    assert!(
+        diff.contains("--- a/custom.py\n+++ /dev/null\n"),
+        "{}",
+        diff
+    );
+    assert!(
+        diff.contains("--- a/old/legacy.py\n+++ /dev/null\n"),
+        "{}",
+        diff
+    );
+    assert!(!diff.contains("__pycache__"), "{}", diff);
+    assert!(!diff.contains("notes.txt"), "{}", diff);
+
+    // Nothing is written in check mode
+    assert_eq!(fs::read_to_string(&item).unwrap(), stale);
+    assert!(!output_dir.join("color.py").exists());
+    assert!(output_dir.join("custom.py").exists());
+}
+
+#[test]
+fn stale_files_are_deleted() {
+    let output_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("stale_files_are_deleted");
+    if output_dir.exists() {
+        fs::remove_dir_all(&output_dir).unwrap();
+    }
+    fixture_builder(&output_dir)
+        .layout(Layout::Modules)
+        .generate()
+        .unwrap();
+    assert!(output_dir.join("net/inline.py").exists());
+    fs::write(
+        output_dir.join("net/custom.py"),
+        "class Custom:\n    pass\n",
+    )
+    .unwrap();
+
+    fixture_builder(&output_dir).generate().unwrap();
+    assert!(output_dir.join("item.py").exists());
+    assert!(!output_dir.join("net/inline.py").exists());
+    assert!(!output_dir.join("net/__init__.py").exists());
+    // Hand-written files are kept, with their directory
+    assert!(output_dir.join("net/custom.py").exists());
+
+    fs::remove_file(output_dir.join("net/custom.py")).unwrap();
+    fixture_builder(&output_dir)
+        .layout(Layout::Modules)
+        .generate()
+        .unwrap();
+    fixture_builder(&output_dir)
+        .layout(Layout::SingleFile)
+        .generate()
+        .unwrap();
+    assert!(!output_dir.join("net").exists());
+    let mut files: Vec<String> = fs::read_dir(&output_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    files.sort();
+    assert_eq!(files, [".rustantic-manifest", "__init__.py", "models.py"]);
+}
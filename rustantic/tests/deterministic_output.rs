@@ -0,0 +1,90 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use rustantic::{Layout, Rustantic};
+
+/// Generates the fixture models and reads every generated file, keyed by relative path.
+fn generate(output_dir: &Path, layout: Layout) -> BTreeMap<PathBuf, String> {
+    if output_dir.exists() {
+        fs::remove_dir_all(output_dir).unwrap();
+    }
+    Rustantic::builder()
+        .crate_dir(Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/models"))
+        .package_name("fixture_models")
+        .output_dir(output_dir)
+        .layout(layout)
+        .generate()
+        .unwrap();
+
+    let mut files = BTreeMap::new();
+    let mut pending = vec![output_dir.to_owned()];
+    while let Some(dir) = pending.pop() {
+        for entry in fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                pending.push(path);
+            } else {
+                let relative_path = path.strip_prefix(output_dir).unwrap().to_owned();
+                files.insert(relative_path, fs::read_to_string(&path).unwrap());
+            }
+        }
+    }
+    files
+}
+
+#[test]
+fn repeated_runs_are_byte_identical() {
+    for layout in [Layout::Flat, Layout::Modules, Layout::SingleFile] {
+        let base = Path::new(env!("CARGO_TARGET_TMPDIR"))
+            .join("repeated_runs")
+            .join(format!("{:?}", layout));
+        let snapshot = generate(&base.join("0"), layout);
+        assert!(!snapshot.is_empty());
+        for (path, code) in snapshot.iter() {
+            for line in code.lines() {
+                assert_eq!(
+                    line,
+                    line.trim_end(),
+                    "trailing whitespace in {}",
+                    path.display()
+                );
+            }
+            assert!(code.ends_with('\n'), "{}", path.display());
+        }
+
+        // Every run hashes with new keys, iteration orders differ between runs
+        for run in 1..5 {
+            let files = generate(&base.join(run.to_string()), layout);
+            assert_eq!(
+                files.keys().collect::<Vec<_>>(),
+                snapshot.keys().collect::<Vec<_>>()
+            );
+            for (path, code) in files.iter() {
+                assert_eq!(
+                    code,
+                    &snapshot[path],
+                    "{:?} {} differs",
+                    layout,
+                    path.display()
+                );
+            }
+        }
+    }
+}
+
+/// The committed models of `rustantic_test` are the snapshot of the generator output.
+#[test]
+fn committed_models_are_up_to_date() {
+    let crate_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../rustantic_test");
+    let result = Rustantic::builder()
+        .crate_dir(&crate_dir)
+        .package_name("rustantic_test")
+        .models_package_name("rustantic_test.generated")
+        .output_dir(crate_dir.join("rustantic_test/generated"))
+        .check(true)
+        .generate();
+    if let Err(err) = result {
+        panic!("{}", err);
+    }
+}
@@ -0,0 +1,298 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use rustantic::collector::MetadataCollector;
+use rustantic::models::ItemMetadata;
+use rustantic::{
+    Backend, Diagnostic, Error, GenerationResult, GeneratorConfig, PydanticCodeGenerator,
+    PydanticCodeGeneratorFactory, Rustantic, RustanticBuilder, Severity, Verbosity,
+};
+
+fn fixture_builder(test_name: &str) -> (RustanticBuilder, PathBuf) {
+    let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/models");
+    let output_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join(test_name);
+    if output_dir.exists() {
+        fs::remove_dir_all(&output_dir).unwrap();
+    }
+    let builder = Rustantic::builder()
+        .crate_dir(fixture)
+        .package_name("fixture_models")
+        .output_dir(&output_dir);
+    (builder, output_dir)
+}
+
+/// A crate with `source` as its `src/lib.rs`.
+fn crate_builder(test_name: &str, source: &str) -> (RustanticBuilder, PathBuf) {
+    let crate_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join(test_name);
+    if crate_dir.exists() {
+        fs::remove_dir_all(&crate_dir).unwrap();
+    }
+    fs::create_dir_all(crate_dir.join("src")).unwrap();
+    fs::write(crate_dir.join("src/lib.rs"), source).unwrap();
+    let output_dir = crate_dir.join("generated");
+    let builder = Rustantic::builder()
+        .crate_dir(&crate_dir)
+        .package_name("models")
+        .output_dir(&output_dir);
+    (builder, output_dir)
+}
+
+fn diagnostics(result: Result<(), Error>) -> Vec<Diagnostic> {
+    match result {
+        Err(Error::Diagnostics(diagnostics)) => diagnostics,
+        other => panic!("expected diagnostics, got {:?}", other),
+    }
+}
+
+struct FailingGenerator {}
+
+impl PydanticCodeGeneratorFactory for FailingGenerator {
+    fn create() -> Box<dyn PydanticCodeGenerator> {
+        Box::new(Self {})
+    }
+}
+
+impl PydanticCodeGenerator for FailingGenerator {
+    fn is_item_supported(&self, meta: &ItemMetadata) -> bool {
+        matches!(
+            meta,
+            ItemMetadata::UnitEnum(_) | ItemMetadata::DiscriminatedUnion(_)
+        )
+    }
+
+    fn generate(
+        &self,
+        _: GeneratorConfig,
+        _: &MetadataCollector,
+        meta: &ItemMetadata,
+    ) -> Result<GenerationResult, Error> {
+        Err(Error::Generation(format!("can't generate {}", meta.name())))
+    }
+}
+
+#[test]
+fn generation_errors_are_aggregated() {
+    let (builder, output_dir) = fixture_builder("generation_errors_are_aggregated");
+    let result = builder.register::<FailingGenerator>().generate();
+    let diagnostics = diagnostics(result);
+
+    let errors: Vec<&Diagnostic> = diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.severity == Severity::Error)
+        .collect();
+    assert!(errors.len() > 1, "{:?}", diagnostics);
+    let color = errors
+        .iter()
+        .find(|diagnostic| diagnostic.item.as_deref() == Some("crate::Color"))
+        .unwrap();
+    assert_eq!(color.message, "can't generate Color");
+    assert_eq!(color.file.as_deref(), Some(Path::new("src/lib.rs")));
+    assert_eq!(color.line, Some(16));
+    assert_eq!(
+        color.to_string(),
+        "src/lib.rs:16: 'crate::Color': can't generate Color"
+    );
+    assert!(!output_dir.exists(), "nothing is written on errors");
+}
+
+#[test]
+fn warnings_as_errors() {
+    let (builder, output_dir) = fixture_builder("warnings_as_errors");
+    let builder = builder.backends([Backend::Structs, Backend::UnitEnums]);
+    builder.clone().generate().unwrap();
+    assert!(output_dir.join("item.py").exists());
+
+    let diagnostics = diagnostics(builder.warnings_as_errors(true).generate());
+    let event = diagnostics
+        .iter()
+        .find(|diagnostic| diagnostic.item.as_deref() == Some("crate::Event"))
+        .unwrap();
+    assert_eq!(event.severity, Severity::Warning);
+    assert_eq!(event.line, Some(35));
+    assert!(diagnostics
+        .iter()
+        .all(|diagnostic| diagnostic.severity == Severity::Warning));
+}
+
+#[test]
+fn log_file_has_the_summary() {
+    let (builder, output_dir) = fixture_builder("log_file_has_the_summary");
+    let log_file = output_dir.join("logs/rustantic.log");
+    builder
+        .backends([Backend::Structs])
+        .verbosity(Verbosity::Quiet)
+        .log_file(&log_file)
+        .generate()
+        .unwrap();
+
+    let log = fs::read_to_string(log_file).unwrap();
+    assert!(log.contains("collected 'crate::Item'"), "{}", log);
+    assert!(log.contains("wrote item.py"), "{}", log);
+    assert!(
+        log.contains("'crate::Color': skipped, no registered generator supports it"),
+        "{}",
+        log
+    );
+    assert!(
+        log.lines().last().unwrap().starts_with("generated "),
+        "{}",
+        log
+    );
+}
+
+#[test]
+fn skipped_required_arguments_are_errors() {
+    let (builder, output_dir) = crate_builder(
+        "skipped_required_arguments_are_errors",
+        r#"
+#[pydantic]
+pub struct Session {
+    pub user: String,
+    #[pydantic(skip)]
+    pub token: String,
+}
+
+#[pymethods]
+impl Session {
+    #[new]
+    fn new(user: String, token: String) -> Self {
+        Self { user, token }
+    }
+}
+
+#[pydantic]
+pub enum Command {
+    Login {
+        user: String,
+        #[pydantic(skip)]
+        token: String,
+    },
+}
+"#,
+    );
+    let diagnostics = diagnostics(builder.generate());
+    let messages: Vec<String> = diagnostics.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        messages,
+        [
+            "src/lib.rs:18: 'crate::Command': `skip` on 'token' of variant 'Login' leaves out an \
+             argument of the variant constructor",
+            "src/lib.rs:3: 'crate::Session': `skip` on 'token' leaves out a required argument of \
+             the #[new] constructor, give it a default in the pyo3 signature",
+        ]
+    );
+    assert!(!output_dir.exists());
+}
+
+#[test]
+fn variant_models_collide_with_other_models() {
+    let (builder, _) = crate_builder(
+        "variant_models_collide_with_other_models",
+        r#"
+#[pydantic]
+pub enum Shape {
+    Circle { radius: f64 },
+}
+
+#[pydantic]
+pub struct ShapeCircle {
+    pub radius: f64,
+}
+"#,
+    );
+    let diagnostics = diagnostics(builder.warnings_as_errors(true).generate());
+    let messages: Vec<String> = diagnostics.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        messages,
+        [
+            "src/lib.rs:8: 'crate::ShapeCircle': model name 'ShapeCircle' is already used by a \
+          variant of 'crate::Shape', it isn't generated. Use #[pydantic(name = \"...\")] to \
+          give it a unique name"
+        ]
+    );
+}
+
+#[test]
+fn colliding_field_attributes_are_errors() {
+    let (builder, output_dir) = crate_builder(
+        "colliding_field_attributes_are_errors",
+        r#"
+#[pydantic]
+pub struct Record {
+    pub id: u64,
+    pub _id: u64,
+}
+"#,
+    );
+    let diagnostics = diagnostics(builder.generate());
+    let messages: Vec<String> = diagnostics.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        messages,
+        ["src/lib.rs:3: 'crate::Record': fields 'id' and '_id' are both generated as 'id', rename \
+          one of them with #[pydantic(rename = \"...\")]"]
+    );
+    assert!(!output_dir.exists());
+}
+
+#[test]
+fn skipped_arguments_with_defaults_are_left_out() {
+    let (builder, output_dir) = crate_builder(
+        "skipped_arguments_with_defaults_are_left_out",
+        r#"
+#[pydantic]
+pub struct Session {
+    pub user: String,
+    #[pydantic(skip)]
+    pub token: String,
+}
+
+#[pymethods]
+impl Session {
+    #[new]
+    #[pyo3(signature = (user, token=String::new()))]
+    fn new(user: String, token: String) -> Self {
+        Self { user, token }
+    }
+}
+
+#[pydantic(new)]
+pub struct Cache {
+    pub key: String,
+    #[pydantic(skip)]
+    pub hits: u64,
+}
+"#,
+    );
+    builder.generate().unwrap();
+    let session = fs::read_to_string(output_dir.join("session.py")).unwrap();
+    assert!(!session.contains("token"), "{}", session);
+    let cache = fs::read_to_string(output_dir.join("cache.py")).unwrap();
+    assert!(!cache.contains("hits"), "{}", cache);
+}
+
+#[test]
+fn missing_from_rs_is_a_warning() {
+    let (builder, _) = fixture_builder("missing_from_rs_is_a_warning");
+    let diagnostics = diagnostics(builder.warnings_as_errors(true).generate());
+    let messages: Vec<String> = diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.message.starts_with("from_rs()"))
+        .map(|diagnostic| {
+            format!(
+                "{}: {}",
+                diagnostic.item.as_deref().unwrap(),
+                diagnostic.message
+            )
+        })
+        .collect();
+    assert_eq!(
+        messages,
+        [
+            "crate::Account: from_rs() is not generated, the #[new] arguments 'initial' have no \
+             field of the same name to read them from",
+            "crate::Badge: from_rs() is not generated, the fields 'level' have no getter, add \
+             #[pydantic(get_all)] or #[pyo3(get)]",
+            "crate::Wallet: from_rs() is not generated, 'crate::Account' has no from_rs()",
+        ]
+    );
+}
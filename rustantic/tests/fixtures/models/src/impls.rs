@@ -0,0 +1,11 @@
+use pyo3::prelude::*;
+
+use crate::net::{inline::Settings as NetSettings, Endpoint};
+
+#[pymethods]
+impl Endpoint {
+    #[new]
+    fn new(host: String, settings: NetSettings) -> Self {
+        Self { host, settings }
+    }
+}
@@ -0,0 +1,9 @@
+use pyo3::prelude::*;
+use rustantic_macros::pydantic;
+
+// Same model name as `crate::Item`, reported as a collision
+#[pydantic(new, get_all)]
+#[derive(Clone)]
+pub struct Item {
+    pub id: u64,
+}
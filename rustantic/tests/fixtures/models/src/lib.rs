@@ -0,0 +1,241 @@
+// Scanned by the generator tests, never compiled.
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use pyo3::prelude::*;
+use rust_decimal::Decimal;
+use rustantic_macros::pydantic;
+
+mod impls;
+mod legacy;
+mod net;
+
+use net::Endpoint;
+
+#[pydantic]
+#[derive(Clone, Hash, Eq)]
+pub enum Color {
+    Red,
+    Green,
+    Blue,
+}
+
+#[pydantic(new, get_all)]
+#[derive(Clone)]
+pub struct Item {
+    pub name: String,
+    pub qty: u32,
+}
+
+#[pydantic(new, get_all)]
+#[derive(Clone)]
+pub struct Pair(i64, Item);
+
+#[pydantic]
+#[derive(Clone)]
+pub enum Event {
+    Created { item: Item },
+    Renamed(String, String),
+    Cleared(),
+}
+
+#[pydantic(new, get_all)]
+#[derive(Clone)]
+pub struct Conversions {
+    pub items: Vec<Item>,
+    pub tagged: Vec<(String, Item)>,
+    pub corner: (i32, Color),
+    pub single: (Item,),
+    pub rgb: [u16; 3],
+    pub grid: [[Color; 2]; 2],
+    pub by_zone: HashMap<String, HashMap<String, Color>>,
+    pub by_name: BTreeMap<String, Vec<Item>>,
+    pub limits: Option<HashMap<String, Item>>,
+    pub maybe_items: Option<Vec<Option<Item>>>,
+    pub colors: HashSet<Color>,
+    pub pair: Pair,
+    pub events: Vec<Event>,
+}
+
+#[pydantic(new, get_all)]
+#[derive(Clone)]
+pub struct TreeNode {
+    pub value: i32,
+    pub children: Vec<TreeNode>,
+    pub next: Option<Box<TreeNode>>,
+}
+
+#[pydantic(new, get_all)]
+#[derive(Clone)]
+pub struct Team {
+    pub name: String,
+    pub members: Vec<Member>,
+}
+
+#[pydantic(new, get_all)]
+#[derive(Clone)]
+pub struct Member {
+    pub name: String,
+    pub teams: Vec<Team>,
+}
+
+#[pydantic(new, get_all)]
+#[derive(Clone)]
+pub struct Org {
+    pub root: Team,
+    pub tree: TreeNode,
+}
+
+#[pydantic]
+#[derive(Clone)]
+pub enum Expr {
+    Lit(i64),
+    Add(Box<Expr>, Box<Expr>),
+    Neg { expr: Box<Expr> },
+}
+
+#[pydantic(new, get_all)]
+#[derive(Clone)]
+pub struct Route {
+    pub endpoint: Endpoint,
+    pub fallback: Option<net::Endpoint>,
+    pub settings: self::net::inline::Settings,
+}
+
+/// Not a model, mapped by the tests.
+#[pyclass]
+#[derive(Clone)]
+pub struct Money(Decimal);
+
+#[pydantic(new, get_all)]
+#[derive(Clone)]
+pub struct Ledger {
+    pub total: Decimal,
+    pub fees: Vec<rust_decimal::Decimal>,
+    pub balance: Money,
+    pub history: Option<Vec<Money>>,
+}
+
+#[pydantic(new, get_all)]
+#[derive(Clone)]
+pub struct Counters {
+    pub small: u8,
+    pub delta: i8,
+    pub huge: i128,
+    pub total: u128,
+    pub index: usize,
+    pub offset: isize,
+    pub id: std::num::NonZeroU32,
+    pub step: std::num::NonZero<i16>,
+    #[pydantic(ge = 10)]
+    pub port: u16,
+    pub samples: Vec<u16>,
+}
+
+#[pydantic(new, get_all)]
+#[derive(Clone)]
+pub struct Measurements {
+    pub ratio: f32,
+    pub mean: f64,
+    pub key: ordered_float::OrderedFloat<f64>,
+    pub score: ordered_float::NotNan<f32>,
+    pub samples: Vec<f32>,
+    #[pydantic(ge = 0)]
+    pub limit: Option<f64>,
+}
+
+#[pydantic(new, get_all)]
+#[derive(Clone)]
+pub struct Blob {
+    pub payload: Vec<u8>,
+    pub digest: [u8; 4],
+    pub frame: bytes::Bytes,
+    pub chunks: Vec<Vec<u8>>,
+    pub checksum: Option<Vec<u8>>,
+    pub levels: Vec<u16>,
+}
+
+#[pydantic(get_all)]
+#[derive(Clone)]
+pub struct Account {
+    pub owner: String,
+    pub balance: u64,
+}
+
+#[pymethods]
+impl Account {
+    #[new]
+    fn new(owner: String, initial: u64) -> Self {
+        Self {
+            owner,
+            balance: initial,
+        }
+    }
+}
+
+#[pydantic(new, get_all)]
+#[derive(Clone)]
+pub struct Wallet {
+    pub account: Account,
+}
+
+#[pydantic(get_all)]
+#[derive(Clone)]
+pub struct Tag {
+    pub label: String,
+}
+
+#[pymethods]
+impl Tag {
+    #[new]
+    #[pyo3(signature = (label, color=None))]
+    fn new(label: String, color: Option<String>) -> Self {
+        let _ = color;
+        Self { label }
+    }
+}
+
+#[pydantic(new)]
+#[derive(Clone)]
+pub struct Opaque {
+    pub secret: String,
+}
+
+#[pydantic(new)]
+#[derive(Clone)]
+pub struct Badge {
+    #[pyo3(get)]
+    pub name: String,
+    pub level: u8,
+}
+
+#[pydantic(new, get_all)]
+#[derive(Clone)]
+pub struct Draft {
+    pub title: String,
+    pub _note: Option<String>,
+    #[pydantic(ge = 1)]
+    pub _revision: u32,
+}
+
+#[pydantic(get_all)]
+#[derive(Clone)]
+pub struct Task {
+    pub name: String,
+    pub _tags: Vec<String>,
+}
+
+#[pymethods]
+impl Task {
+    #[new]
+    #[pyo3(signature = (name, _tags, _retries=None))]
+    fn new(name: String, _tags: Vec<String>, _retries: Option<i32>) -> Self {
+        Self { name, _tags }
+    }
+}
+
+#[pydantic(new, get_all)]
+#[derive(Clone)]
+pub struct Span(
+    #[pydantic(description = "First line")] u32,
+    #[pydantic(ge = 1, le = 80)] u8,
+);
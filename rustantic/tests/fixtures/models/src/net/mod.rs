@@ -0,0 +1,27 @@
+use pyo3::prelude::*;
+use rustantic_macros::pydantic;
+
+pub mod inline {
+    use pyo3::prelude::*;
+    use rustantic_macros::pydantic;
+
+    #[pydantic(new, get_all)]
+    #[derive(Clone)]
+    pub struct Settings {
+        pub retries: u8,
+    }
+}
+
+#[pydantic(get_all)]
+#[derive(Clone)]
+pub struct Endpoint {
+    pub host: String,
+    pub settings: inline::Settings,
+}
+
+// References back to the crate root, a cycle between the modules of the modules layout
+#[pydantic(new, get_all)]
+#[derive(Clone)]
+pub struct Mirror {
+    pub route: Option<Box<crate::Route>>,
+}
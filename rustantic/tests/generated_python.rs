@@ -0,0 +1,830 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::{env, fs};
+
+use rustantic::attributes::ConstraintValue;
+use rustantic::collector::MetadataCollector;
+use rustantic::models::ItemMetadata;
+use rustantic::{
+    Backend, Error, F32Precision, GenerationResult, GeneratorConfig, Layout, Naming,
+    PydanticCodeGenerator, PydanticCodeGeneratorFactory, PythonVersion, Rustantic,
+    RustanticBuilder, TypeMapping,
+};
+
+const PACKAGE: &str = "fixture_models";
+
+/// Stands in for the pyo3 extension module: every attribute is a class storing its
+/// constructor arguments, positional ones as `_0`, `_1`, ... like pyo3 tuple fields.
+const NATIVE_STUB: &str = r#"
+class _StubType(type):
+    def __getattr__(cls, name):
+        if name.startswith("__"):
+            raise AttributeError(name)
+        child = _StubType(name, (_StubObject,), {})
+        setattr(cls, name, child)
+        return child
+
+
+class _StubObject(metaclass=_StubType):
+    def __init__(self, *args, **kwargs):
+        for idx, arg in enumerate(args):
+            setattr(self, f"_{idx}", arg)
+        for key, value in kwargs.items():
+            setattr(self, key, value)
+
+
+_classes = {}
+
+
+def __getattr__(name):
+    if name.startswith("__"):
+        raise AttributeError(name)
+    return _classes.setdefault(name, _StubType(name, (_StubObject,), {}))
+"#;
+
+fn python() -> Option<String> {
+    let python = env::var("RUSTANTIC_TEST_PYTHON").unwrap_or_else(|_| "python3".to_owned());
+    let available = Command::new(&python)
+        .args(["-c", "import pydantic"])
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false);
+    if available {
+        Some(python)
+    } else {
+        eprintln!("skipping, `{}` with pydantic is not available", python);
+        None
+    }
+}
+
+/// Generates the fixture models into a fresh python path and returns it.
+fn generate_fixture(test_name: &str) -> PathBuf {
+    generate_fixture_with(test_name, |builder| builder)
+}
+
+fn generate_fixture_with_layout(test_name: &str, layout: Layout) -> PathBuf {
+    generate_fixture_with(test_name, |builder| builder.layout(layout))
+}
+
+fn generate_fixture_with(
+    test_name: &str,
+    configure: impl FnOnce(RustanticBuilder) -> RustanticBuilder,
+) -> PathBuf {
+    let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/models");
+    let python_path = Path::new(env!("CARGO_TARGET_TMPDIR")).join(test_name);
+    let package_dir = python_path.join(PACKAGE);
+    if python_path.exists() {
+        fs::remove_dir_all(&python_path).unwrap();
+    }
+    fs::create_dir_all(&package_dir).unwrap();
+    fs::write(package_dir.join("__init__.py"), NATIVE_STUB).unwrap();
+
+    let builder = Rustantic::builder()
+        .crate_dir(fixture)
+        .package_name(PACKAGE)
+        .output_dir(package_dir.join("generated"));
+    configure(builder).generate().unwrap();
+    python_path
+}
+
+fn run_python(python: &str, python_path: &Path, code: &str) {
+    let output = Command::new(python)
+        .args(["-c", code])
+        .current_dir(python_path)
+        .env("PYTHONPATH", python_path)
+        .env("PYTHONDONTWRITEBYTECODE", "1")
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "python failed:\n{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+fn generated_modules_import() {
+    let Some(python) = python() else {
+        return;
+    };
+    let python_path = generate_fixture("generated_modules_import");
+    run_python(
+        &python,
+        &python_path,
+        r#"
+import importlib, pkgutil
+import fixture_models.generated as generated
+
+modules = sorted(m.name for m in pkgutil.iter_modules(generated.__path__))
+assert modules, "nothing was generated"
+for name in modules:
+    importlib.import_module(f"fixture_models.generated.{name}")
+for name in generated.__all__:
+    getattr(generated, name)
+"#,
+    );
+}
+
+/// The committed `rustantic_test` models, imported with the stub in place of the extension.
+#[test]
+fn committed_models_import() {
+    let Some(python) = python() else {
+        return;
+    };
+    let committed = Path::new(env!("CARGO_MANIFEST_DIR")).join("../rustantic_test/rustantic_test");
+    let python_path = Path::new(env!("CARGO_TARGET_TMPDIR")).join("committed_models_import");
+    let package_dir = python_path.join("rustantic_test");
+    if python_path.exists() {
+        fs::remove_dir_all(&python_path).unwrap();
+    }
+    fs::create_dir_all(package_dir.join("generated")).unwrap();
+    fs::write(package_dir.join("__init__.py"), NATIVE_STUB).unwrap();
+    for entry in fs::read_dir(committed.join("generated")).unwrap() {
+        let path = entry.unwrap().path();
+        fs::copy(
+            &path,
+            package_dir
+                .join("generated")
+                .join(path.file_name().unwrap()),
+        )
+        .unwrap();
+    }
+    run_python(
+        &python,
+        &python_path,
+        r#"
+from rustantic_test.generated import MyClass, MyUnitEnum, Nested
+
+nested = Nested(name="n", num=1, id=7)
+model = MyClass(
+    name="c", num2=2, vec=[], nested=nested, myenum=MyUnitEnum.A, _nesteds=[nested], _opt1=5
+)
+native = model.to_rs()
+assert native._opt1 == 5
+assert [n.name for n in native._nesteds] == ["n"]
+"#,
+    );
+}
+
+#[test]
+fn conversions_round_trip() {
+    let Some(python) = python() else {
+        return;
+    };
+    let python_path = generate_fixture("conversions_round_trip");
+    run_python(
+        &python,
+        &python_path,
+        r#"
+from pydantic import BaseModel
+from fixture_models.generated import Color, Conversions, Event, Item, Pair
+from fixture_models.generated.event import EventCleared, EventCreated, EventRenamed
+
+
+def assert_native(value):
+    assert not isinstance(value, (BaseModel, Color)), f"{value!r} was not converted"
+    if isinstance(value, dict):
+        for key, item in value.items():
+            assert_native(key)
+            assert_native(item)
+    elif isinstance(value, (list, tuple, set)):
+        for item in value:
+            assert_native(item)
+    elif hasattr(value, "__dict__"):
+        for item in vars(value).values():
+            assert_native(item)
+
+
+item = Item(name="a", qty=1)
+model = Conversions(
+    items=[item],
+    tagged=[("x", item)],
+    corner=(3, Color.Green),
+    single=(item,),
+    rgb=[1, 2, 3],
+    grid=[[Color.Red, Color.Blue], [Color.Green, Color.Red]],
+    by_zone={"eu": {"fr": Color.Blue}},
+    by_name={"a": [item, Item(name="b", qty=2)]},
+    limits={"max": item},
+    maybe_items=[item, None],
+    colors={Color.Red, Color.Green},
+    pair=Pair((7, item)),
+    events=[
+        Event(EventCreated(item=item)),
+        Event(EventRenamed(field_0="a", field_1="b")),
+        Event(EventCleared()),
+    ],
+)
+native = model.to_rs()
+assert_native(native)
+assert Conversions.from_rs(native) == model
+
+empty = model.model_copy(update={"limits": None, "maybe_items": None})
+assert Conversions.from_rs(empty.to_rs()) == empty
+"#,
+    );
+}
+
+#[test]
+fn recursive_models_round_trip() {
+    let Some(python) = python() else {
+        return;
+    };
+    let python_path = generate_fixture("recursive_models_round_trip");
+    run_python(
+        &python,
+        &python_path,
+        r#"
+from fixture_models.generated import Expr, Member, Org, Team, TreeNode
+from fixture_models.generated.expr import ExprAdd, ExprLit, ExprNeg
+
+leaf = TreeNode(value=2, children=[])
+tree = TreeNode(value=1, children=[leaf], next=TreeNode(value=3, children=[leaf]))
+assert TreeNode.from_rs(tree.to_rs()) == tree
+
+team = Team(name="core", members=[Member(name="a", teams=[Team(name="infra", members=[])])])
+assert Team.from_rs(team.to_rs()) == team
+
+org = Org(root=team, tree=tree)
+assert Org.from_rs(org.to_rs()) == org
+assert Org.model_validate(org.model_dump()) == org
+
+expr = Expr(ExprAdd(field_0=Expr(ExprLit(value=1)), field_1=Expr(ExprNeg(expr=Expr(ExprLit(value=2))))))
+assert Expr.from_rs(expr.to_rs()) == expr
+"#,
+    );
+}
+
+#[test]
+fn module_paths_resolve() {
+    let Some(python) = python() else {
+        return;
+    };
+    let python_path = generate_fixture("module_paths_resolve");
+    run_python(
+        &python,
+        &python_path,
+        r#"
+from fixture_models.generated import Endpoint, Item, Route, Settings
+
+settings = Settings(retries=2)
+endpoint = Endpoint(host="localhost", settings=settings)
+route = Route(endpoint=endpoint, fallback=endpoint, settings=settings)
+assert Route.from_rs(route.to_rs()) == route
+
+# `crate::legacy::Item` collides with `crate::Item`, the first one by path is kept
+assert set(Item.model_fields) == {"name", "qty"}
+"#,
+    );
+}
+
+#[test]
+fn modules_layout_round_trip() {
+    let Some(python) = python() else {
+        return;
+    };
+    let python_path = generate_fixture_with_layout("modules_layout_round_trip", Layout::Modules);
+    for file in [
+        "__init__.py",
+        "legacy.py",
+        "net/__init__.py",
+        "net/inline.py",
+    ] {
+        let path = python_path.join(PACKAGE).join("generated").join(file);
+        assert!(path.exists(), "{} wasn't generated", path.display());
+    }
+    run_python(
+        &python,
+        &python_path,
+        r#"
+import fixture_models.generated as generated
+from fixture_models.generated import Endpoint, Mirror, Route, Settings, TreeNode
+from fixture_models.generated.legacy import Item as LegacyItem
+from fixture_models.generated.net import inline
+
+assert Endpoint.__module__ == "fixture_models.generated.net"
+assert Settings is inline.Settings
+# Both `crate::Item` and `crate::legacy::Item` are generated, in their own module
+assert generated.Item is not LegacyItem
+assert set(LegacyItem.model_fields) == {"id"}
+assert LegacyItem.from_rs(LegacyItem(id=3).to_rs()) == LegacyItem(id=3)
+
+settings = Settings(retries=2)
+endpoint = Endpoint(host="localhost", settings=settings)
+route = Route(endpoint=endpoint, fallback=None, settings=settings)
+assert Route.from_rs(route.to_rs()) == route
+
+mirror = Mirror(route=route)
+assert Mirror.from_rs(mirror.to_rs()) == mirror
+assert Mirror.model_validate(mirror.model_dump()) == mirror
+
+tree = TreeNode(value=1, children=[TreeNode(value=2, children=[])])
+assert TreeNode.from_rs(tree.to_rs()) == tree
+"#,
+    );
+}
+
+#[test]
+fn single_file_layout_round_trip() {
+    let Some(python) = python() else {
+        return;
+    };
+    let python_path =
+        generate_fixture_with_layout("single_file_layout_round_trip", Layout::SingleFile);
+    let generated = python_path.join(PACKAGE).join("generated");
+    let mut files: Vec<String> = fs::read_dir(&generated)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    files.sort();
+    assert_eq!(files, [".rustantic-manifest", "__init__.py", "models.py"]);
+    run_python(
+        &python,
+        &python_path,
+        r#"
+from fixture_models.generated import models
+from fixture_models.generated import Color, Endpoint, Expr, Item, Mirror, Route, Settings, Team, Member
+from fixture_models.generated.models import ExprLit, ExprNeg
+
+source = open(models.__file__).read()
+assert source.index("class Settings(") < source.index("class Endpoint(") < source.index("class Route(")
+import_lines = [line for line in source.splitlines() if line.startswith(("import ", "from "))]
+assert len(import_lines) == len(set(import_lines))
+assert len([line for line in import_lines if line.startswith("from pydantic import")]) == 1
+
+settings = Settings(retries=2)
+route = Route(endpoint=Endpoint(host="localhost", settings=settings), settings=settings)
+mirror = Mirror(route=route)
+assert Mirror.from_rs(mirror.to_rs()) == mirror
+
+team = Team(name="core", members=[Member(name="a", teams=[])])
+assert Team.from_rs(team.to_rs()) == team
+
+expr = Expr(ExprNeg(expr=Expr(ExprLit(value=1))))
+assert Expr.from_rs(expr.to_rs()) == expr
+"#,
+    );
+}
+
+#[test]
+fn builder_options() {
+    let Some(python) = python() else {
+        return;
+    };
+    let python_path = generate_fixture_with("builder_options", |builder| {
+        builder
+            .python_version(PythonVersion::new(3, 9))
+            .naming(Naming::CamelCase)
+            .header("Generated code\nDo not edit")
+            .backends([Backend::Structs, Backend::UnitEnums])
+    });
+    let generated = python_path.join(PACKAGE).join("generated");
+    assert!(!generated.join("expr.py").exists());
+    for file in ["__init__.py", "color.py", "conversions.py"] {
+        let code = fs::read_to_string(generated.join(file)).unwrap();
+        assert!(
+            code.starts_with("# Generated code\n# Do not edit\n"),
+            "{}",
+            code
+        );
+        assert!(!code.contains("match "), "{}", code);
+    }
+    run_python(
+        &python,
+        &python_path,
+        r#"
+from fixture_models.generated import Color, Item, Mirror, Route, Settings, Endpoint
+import fixture_models.generated as generated
+
+assert "Expr" not in generated.__all__
+assert Color.from_rs(Color.Blue.to_rs()) is Color.Blue
+settings = Settings(retries=1)
+route = Route(endpoint=Endpoint(host="h", settings=settings), settings=settings)
+assert Route.from_rs(route.to_rs()) == route
+assert "route" in Mirror.model_fields
+
+from fixture_models.generated import Conversions, Pair
+assert {"byZone", "byName", "maybeItems"} <= set(Conversions.model_fields)
+item = Item(name="a", qty=1)
+model = Conversions(
+    items=[item],
+    tagged=[],
+    corner=(1, Color.Red),
+    single=(item,),
+    rgb=[1, 2, 3],
+    grid=[[Color.Red, Color.Red], [Color.Green, Color.Green]],
+    byZone={"eu": {"fr": Color.Blue}},
+    byName={"a": [item]},
+    colors=set(),
+    pair=Pair((7, item)),
+    events=[],
+)
+assert Conversions.from_rs(model.to_rs()) == model
+"#,
+    );
+}
+
+/// Generates unit enums as string enums, ahead of the built-in generator.
+struct StrEnumGenerator {}
+
+impl PydanticCodeGeneratorFactory for StrEnumGenerator {
+    fn create() -> Box<dyn PydanticCodeGenerator> {
+        Box::new(Self {})
+    }
+}
+
+impl PydanticCodeGenerator for StrEnumGenerator {
+    fn is_item_supported(&self, meta: &ItemMetadata) -> bool {
+        matches!(meta, ItemMetadata::UnitEnum(_))
+    }
+
+    fn generate(
+        &self,
+        config: GeneratorConfig,
+        _: &MetadataCollector,
+        meta: &ItemMetadata,
+    ) -> Result<GenerationResult, Error> {
+        let ItemMetadata::UnitEnum(enum_md) = meta else {
+            return Err(Error::Generation(format!(
+                "'{}' is not a unit enum",
+                meta.path()
+            )));
+        };
+        let mut code = format!("class {}(str, enum.Enum):\n", enum_md.name());
+        for (variant, _) in enum_md.variants.iter() {
+            code.push_str(&format!(
+                "    {} = \"{}\"\n",
+                variant,
+                variant.to_lowercase()
+            ));
+        }
+        code.push_str(&format!(
+            "\n    def to_rs(self):\n        return getattr({}.{}, self.name)\n",
+            config.package_name(),
+            enum_md.name()
+        ));
+        code.push_str(
+            "\n    @classmethod\n    def from_rs(cls, obj):\n        return cls[obj.__name__]\n",
+        );
+
+        let mut result = GenerationResult::new(code);
+        result.additional_imports.insert("import enum".to_owned());
+        result
+            .additional_imports
+            .insert(format!("import {}", config.package_name()));
+        result
+            .init_code
+            .push(format!("{}.__doc__ = \"String enum\"", enum_md.name()));
+        result
+            .warnings
+            .push("generated as a string enum".to_owned());
+        Ok(result)
+    }
+}
+
+#[test]
+fn custom_generator_overrides_backend() {
+    let Some(python) = python() else {
+        return;
+    };
+    let python_path = generate_fixture_with("custom_generator_overrides_backend", |builder| {
+        builder.register::<StrEnumGenerator>()
+    });
+    run_python(
+        &python,
+        &python_path,
+        r#"
+from fixture_models.generated import Color, Conversions, Item, Pair
+
+assert Color.Red == "red"
+assert Color.__doc__ == "String enum"
+assert Color.from_rs(Color.Blue.to_rs()) is Color.Blue
+
+item = Item(name="a", qty=1)
+model = Conversions(
+    items=[],
+    tagged=[],
+    corner=(1, "green"),
+    single=(item,),
+    rgb=[1, 2, 3],
+    grid=[["red", "red"], ["blue", "blue"]],
+    by_zone={},
+    by_name={},
+    colors={"red"},
+    pair=Pair((7, item)),
+    events=[],
+)
+assert model.corner[1] is Color.Green
+assert Conversions.from_rs(model.to_rs()) == model
+"#,
+    );
+}
+
+#[test]
+fn type_mappings_round_trip() {
+    let Some(python) = python() else {
+        return;
+    };
+    // `rust_decimal::Decimal` is mapped by the fixture `rustantic.toml`
+    let python_path = generate_fixture_with("type_mappings_round_trip", |builder| {
+        builder.type_mapping(
+            "Money",
+            TypeMapping::new("decimal.Decimal")
+                .import("import decimal")
+                .constraint("ge", ConstraintValue::Number("0".to_owned()))
+                .to_rs("fixture_models.Money(str({value}))")
+                .from_rs("decimal.Decimal({value}._0)"),
+        )
+    });
+    let code = fs::read_to_string(python_path.join(PACKAGE).join("generated/ledger.py")).unwrap();
+    assert!(!code.contains("Unknown ref type"), "{}", code);
+    run_python(
+        &python,
+        &python_path,
+        r#"
+from decimal import Decimal
+from pydantic import ValidationError
+import fixture_models
+from fixture_models.generated import Ledger
+
+ledger = Ledger(
+    total=Decimal("10.5"),
+    fees=[Decimal("0.5")],
+    balance=Decimal("10"),
+    history=[Decimal("1"), Decimal("9")],
+)
+native = ledger.to_rs()
+assert native.total == Decimal("10.5")
+assert isinstance(native.balance, fixture_models.Money)
+assert all(isinstance(money, fixture_models.Money) for money in native.history)
+assert Ledger.from_rs(native) == ledger
+assert Ledger.from_rs(Ledger(**{**dict(ledger), "history": None}).to_rs()).history is None
+
+try:
+    Ledger(total=Decimal("1"), fees=[], balance=Decimal("-1"))
+except ValidationError:
+    pass
+else:
+    raise AssertionError("the mapping constraints were not applied")
+"#,
+    );
+}
+
+#[test]
+fn integer_ranges_round_trip() {
+    let Some(python) = python() else {
+        return;
+    };
+    let python_path = generate_fixture("integer_ranges_round_trip");
+    run_python(
+        &python,
+        &python_path,
+        r#"
+from pydantic import ValidationError
+from fixture_models.generated import Counters
+
+valid = dict(
+    small=255,
+    delta=-128,
+    huge=-(2**127),
+    total=2**128 - 1,
+    index=2**64 - 1,
+    offset=-(2**63),
+    id=1,
+    step=-1,
+    port=10,
+    samples=[0, 65535],
+)
+counters = Counters(**valid)
+assert Counters.from_rs(counters.to_rs()) == counters
+
+for field, value in [
+    ("small", 256),
+    ("small", -1),
+    ("delta", 128),
+    ("huge", 2**127),
+    ("total", 2**128),
+    ("index", 2**64),
+    ("offset", -(2**63) - 1),
+    ("id", 0),
+    ("step", 0),
+    ("step", 2**15),
+    ("step", -(2**15) - 1),
+    ("port", 9),
+    ("samples", [65536]),
+]:
+    try:
+        Counters(**{**valid, field: value})
+    except ValidationError:
+        pass
+    else:
+        raise AssertionError(f"{field}={value} was accepted")
+"#,
+    );
+}
+
+#[test]
+fn float_options() {
+    let Some(python) = python() else {
+        return;
+    };
+    let default_path = generate_fixture_with("float_options_default", |builder| {
+        builder.f32_precision(F32Precision::Warn)
+    });
+    let strict_path = generate_fixture_with("float_options_strict", |builder| {
+        builder
+            .allow_inf_nan(false)
+            .f32_precision(F32Precision::Validate)
+    });
+    let check = r#"
+import math
+import warnings
+from pydantic import ValidationError
+from fixture_models.generated import Measurements
+
+valid = dict(ratio=0.5, mean=0.1, key=1.0, score=2.0, samples=[0.25], limit=None)
+measurements = Measurements(**valid)
+assert Measurements.from_rs(measurements.to_rs()) == measurements
+
+
+def accepts(**fields):
+    try:
+        Measurements(**{**valid, **fields})
+    except ValidationError:
+        return False
+    return True
+
+
+assert not accepts(score=math.nan)
+assert not accepts(limit=-1.0)
+"#;
+    run_python(
+        &python,
+        &default_path,
+        &format!(
+            "{}{}",
+            check,
+            r#"
+assert accepts(mean=math.inf, key=math.nan, score=math.inf, limit=math.inf)
+with warnings.catch_warnings(record=True) as caught:
+    warnings.simplefilter("always")
+    assert accepts(ratio=0.1, samples=[0.1])
+assert len(caught) == 2, caught
+"#
+        ),
+    );
+    run_python(
+        &python,
+        &strict_path,
+        &format!(
+            "{}{}",
+            check,
+            r#"
+for fields in [
+    dict(ratio=0.1),
+    dict(samples=[0.1]),
+    dict(ratio=1e39),
+    dict(ratio=math.inf),
+    dict(mean=math.nan),
+    dict(key=math.inf),
+    dict(score=math.inf),
+    dict(limit=math.inf),
+]:
+    assert not accepts(**fields), fields
+assert accepts(ratio=0.25, mean=0.1, samples=[1.5])
+"#
+        ),
+    );
+}
+
+#[test]
+fn byte_containers_round_trip() {
+    let Some(python) = python() else {
+        return;
+    };
+    let python_path = generate_fixture("byte_containers_round_trip");
+    let code = fs::read_to_string(python_path.join(PACKAGE).join("generated/blob.py")).unwrap();
+    assert!(code.contains("    payload: bytes\n"), "{}", code);
+    assert!(code.contains("    chunks: list[bytes]\n"), "{}", code);
+    assert!(code.contains("payload=self.payload,"), "{}", code);
+    run_python(
+        &python,
+        &python_path,
+        r#"
+from pydantic import ValidationError
+from fixture_models.generated import Blob
+
+blob = Blob(
+    payload=b"\x00\xff",
+    digest=b"abcd",
+    frame=b"frame",
+    chunks=[b"a", b""],
+    checksum=None,
+    levels=[1, 2],
+)
+native = blob.to_rs()
+assert native.payload == b"\x00\xff"
+assert native.chunks == [b"a", b""]
+assert Blob.from_rs(native) == blob
+
+for digest in [b"abc", b"abcde"]:
+    try:
+        Blob(**{**dict(blob), "digest": digest})
+    except ValidationError:
+        pass
+    else:
+        raise AssertionError(f"digest {digest!r} was accepted")
+"#,
+    );
+}
+
+#[test]
+fn from_rs_needs_every_required_argument() {
+    let Some(python) = python() else {
+        return;
+    };
+    let python_path = generate_fixture("from_rs_needs_every_required_argument");
+    run_python(
+        &python,
+        &python_path,
+        r#"
+from fixture_models.generated import Account, Badge, Opaque, Tag, Wallet
+
+# `initial` can't be read back, neither can the models containing an `Account`
+assert not hasattr(Account, "from_rs")
+assert not hasattr(Wallet, "from_rs")
+assert Account(owner="a", initial=1).to_rs().initial == 1
+
+# `color` has a default, the model falls back to it
+tag = Tag(label="x", color="red")
+assert Tag.from_rs(tag.to_rs()) == Tag(label="x")
+
+# Without getters there is nothing to read from
+assert not hasattr(Opaque, "from_rs")
+assert not hasattr(Badge, "from_rs")
+assert Opaque(secret="s").to_rs().secret == "s"
+"#,
+    );
+}
+
+#[test]
+fn underscore_arguments_round_trip() {
+    let Some(python) = python() else {
+        return;
+    };
+    let python_path = generate_fixture("underscore_arguments_round_trip");
+    run_python(
+        &python,
+        &python_path,
+        r#"
+from pydantic import ValidationError
+from fixture_models.generated import Draft, Task
+
+# The arguments keep their name, the attributes lose the underscore
+draft = Draft(title="t", _note="n", _revision=2)
+assert (draft.note, draft.revision) == ("n", 2)
+native = draft.to_rs()
+assert (native._note, native._revision) == ("n", 2)
+assert Draft.from_rs(native) == draft
+try:
+    Draft(title="t", _revision=0)
+except ValidationError:
+    pass
+else:
+    raise AssertionError("_revision=0 was accepted")
+
+task = Task(name="build", _tags=["ci"], _retries=3)
+native = task.to_rs()
+assert (native._tags, native._retries) == (["ci"], 3)
+assert Task.from_rs(native) == Task(name="build", _tags=["ci"])
+"#,
+    );
+}
+
+#[test]
+fn tuple_field_arguments_apply() {
+    let Some(python) = python() else {
+        return;
+    };
+    let python_path = generate_fixture("tuple_field_arguments_apply");
+    run_python(
+        &python,
+        &python_path,
+        r#"
+from pydantic import ValidationError
+from fixture_models.generated import Span
+
+span = Span((3, 80))
+assert Span.from_rs(span.to_rs()) == span
+assert Span.model_json_schema()["prefixItems"][0]["description"] == "First line"
+for root in [(3, 0), (3, 81), (-1, 1)]:
+    try:
+        Span(root)
+    except ValidationError:
+        pass
+    else:
+        raise AssertionError(f"{root} was accepted")
+"#,
+    );
+}
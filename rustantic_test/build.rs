@@ -1,17 +1,10 @@
 // build.rs
-use std::env;
-
-use rustantic;
-
 fn main() {
-    println!("cargo:warning=Start Rustantic Build Script");
-    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
-    let pydantic_module = "generated";
-    let module_name = "rustantic_test";
-    let py_path = format!("{}/{}/{}", manifest_dir, module_name, pydantic_module);
-    let models_package_name = format!("{}.{}", module_name, pydantic_module);
-    rustantic::generators::generate(module_name, &py_path, &manifest_dir, &models_package_name);
+    // The generator prints the rerun lines of the sources it scans
     println!("cargo:rerun-if-changed=build.rs");
-    println!("cargo:rerun-if-changed=src/lib.rs");
-    println!("cargo:rerun-if-changed=src/*.rs");
+    rustantic::Rustantic::builder()
+        .package_name("rustantic_test")
+        .models_package_name("rustantic_test.generated")
+        .generate()
+        .unwrap_or_else(|err| panic!("{}", err));
 }
@@ -1,13 +1,27 @@
+# Generated by rustantic version: 0.2.0
+
+from .cmd import Cmd
+from .job import Job
+from .marker import Marker
 from .my_class import MyClass
 from .my_enum import MyEnum
 from .my_unit_enum import MyUnitEnum
 from .nested import Nested
 from .nested_2 import Nested2
+from .point import Point
+from .shape import Shape
+from .user_id import UserId
 
 __all__ = [
+    "Cmd",
+    "Job",
+    "Marker",
     "MyClass",
     "MyEnum",
     "MyUnitEnum",
     "Nested",
     "Nested2",
-]
\ No newline at end of file
+    "Point",
+    "Shape",
+    "UserId",
+]
@@ -0,0 +1,66 @@
+# Generated by rustantic version: 0.2.0
+from pydantic import BaseModel, Field, RootModel
+from typing import Any, Literal, Union
+import enum
+import rustantic_test
+
+class CmdDiscriminator(enum.Enum):
+    Stop = enum.auto()
+    Move = enum.auto()
+    Say = enum.auto()
+
+
+class CmdStop(BaseModel):
+    kind: Literal[CmdDiscriminator.Stop] = Field(default=CmdDiscriminator.Stop, init=False, frozen=True)
+
+    def to_rs(self):
+        return rustantic_test.Cmd.Stop()
+
+    @classmethod
+    def from_rs(cls, obj):
+        return cls()
+
+
+class CmdMove(BaseModel):
+    kind: Literal[CmdDiscriminator.Move] = Field(default=CmdDiscriminator.Move, init=False, frozen=True)
+    field_0: int = Field(..., ge=-2147483648, le=2147483647)
+    field_1: int = Field(..., ge=-2147483648, le=2147483647)
+
+    def to_rs(self):
+        return rustantic_test.Cmd.Move(self.field_0, self.field_1)
+
+    @classmethod
+    def from_rs(cls, obj):
+        return cls(field_0=obj._0, field_1=obj._1)
+
+
+class CmdSay(BaseModel):
+    kind: Literal[CmdDiscriminator.Say] = Field(default=CmdDiscriminator.Say, init=False, frozen=True)
+    value: str
+
+    def to_rs(self):
+        return rustantic_test.Cmd.Say(self.value)
+
+    @classmethod
+    def from_rs(cls, obj):
+        return cls(value=obj._0)
+
+
+CmdType = Union[CmdStop,CmdMove,CmdSay]
+
+class Cmd(RootModel[CmdType]):
+    root: CmdType = Field(..., discriminator="kind")
+    def to_rs(self):
+        return self.root.to_rs()
+
+    @classmethod
+    def from_rs(cls, obj):
+        match obj:
+            case rustantic_test.Cmd.Stop():
+                return cls(CmdStop.from_rs(obj))
+            case rustantic_test.Cmd.Move():
+                return cls(CmdMove.from_rs(obj))
+            case rustantic_test.Cmd.Say():
+                return cls(CmdSay.from_rs(obj))
+            case _:
+                raise ValueError(f"Unsupported value '{obj}'")
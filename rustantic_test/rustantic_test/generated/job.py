@@ -0,0 +1,36 @@
+# Generated by rustantic version: 0.2.0
+from pydantic import BaseModel, Field
+from typing import Any
+import rustantic_test
+
+class Job(BaseModel):
+    name: str
+    retries: int = Field(default=3, ge=0, le=255)
+    tags: list[str] = Field(default_factory=list)
+    verbose: bool = False
+    label: str = "job"
+    ratio: float = -0.5
+    options: dict[str, Any] = Field(default_factory=dict)
+
+    def to_rs(self):
+        return rustantic_test.Job(
+            self.name,
+            self.retries,
+            *self.tags,
+            verbose=self.verbose,
+            label=self.label,
+            ratio=self.ratio,
+            **self.options,
+        )
+
+    @classmethod
+    def from_rs(cls, obj):
+        return cls(
+            name=obj.name,
+            retries=obj.retries,
+            tags=obj.tags,
+            verbose=obj.verbose,
+            label=obj.label,
+            ratio=obj.ratio,
+            # 'options' has no matching field on the Rust struct
+        )
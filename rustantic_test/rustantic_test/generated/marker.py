@@ -0,0 +1,11 @@
+# Generated by rustantic version: 0.2.0
+from pydantic import BaseModel, Field
+import rustantic_test
+
+class Marker(BaseModel):
+    def to_rs(self):
+        return rustantic_test.Marker()
+
+    @classmethod
+    def from_rs(cls, obj):
+        return cls()
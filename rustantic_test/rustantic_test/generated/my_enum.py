@@ -1,7 +1,7 @@
 # Generated by rustantic version: 0.2.0
 from pydantic import BaseModel, Field, RootModel
 from rustantic_test.generated.nested import Nested
-from typing import Literal, Union, Any
+from typing import Any, Literal, Union
 import enum
 import rustantic_test
 
@@ -15,15 +15,36 @@ class MyEnumA(BaseModel):
     kind: Literal[MyEnumDiscriminator.A] = Field(default=MyEnumDiscriminator.A, init=False, frozen=True)
     value: Nested
 
+    def to_rs(self):
+        return rustantic_test.MyEnum.A(self.value.to_rs())
+
+    @classmethod
+    def from_rs(cls, obj):
+        return cls(value=Nested.from_rs(obj._0))
+
 
 class MyEnumB(BaseModel):
     kind: Literal[MyEnumDiscriminator.B] = Field(default=MyEnumDiscriminator.B, init=False, frozen=True)
     value: Nested
 
+    def to_rs(self):
+        return rustantic_test.MyEnum.B(self.value.to_rs())
+
+    @classmethod
+    def from_rs(cls, obj):
+        return cls(value=Nested.from_rs(obj._0))
+
 
 class MyEnumC(BaseModel):
     kind: Literal[MyEnumDiscriminator.C] = Field(default=MyEnumDiscriminator.C, init=False, frozen=True)
-    value: int
+    value: int = Field(..., ge=-32768, le=32767)
+
+    def to_rs(self):
+        return rustantic_test.MyEnum.C(self.value)
+
+    @classmethod
+    def from_rs(cls, obj):
+        return cls(value=obj._0)
 
 
 MyEnumType = Union[MyEnumA,MyEnumB,MyEnumC]
@@ -31,14 +52,16 @@ MyEnumType = Union[MyEnumA,MyEnumB,MyEnumC]
 class MyEnum(RootModel[MyEnumType]):
     root: MyEnumType = Field(..., discriminator="kind")
     def to_rs(self):
-        inner_to_rs = getattr(self.root.value, "to_rs", lambda v: v)
-        val: Any = inner_to_rs(self.root.value)
-        match self.root.kind:
-            case MyEnumDiscriminator.A:
-                return rustantic_test.MyEnum.A(val)
-
-            case MyEnumDiscriminator.B:
-                return rustantic_test.MyEnum.B(val)
+        return self.root.to_rs()
 
-            case MyEnumDiscriminator.C:
-                return rustantic_test.MyEnum.C(val)
+    @classmethod
+    def from_rs(cls, obj):
+        match obj:
+            case rustantic_test.MyEnum.A():
+                return cls(MyEnumA.from_rs(obj))
+            case rustantic_test.MyEnum.B():
+                return cls(MyEnumB.from_rs(obj))
+            case rustantic_test.MyEnum.C():
+                return cls(MyEnumC.from_rs(obj))
+            case _:
+                raise ValueError(f"Unsupported value '{obj}'")
@@ -20,3 +20,17 @@ class MyUnitEnum(enum.Enum):
                 return rustantic_test.MyUnitEnum.D
             case _:
                 raise ValueError(f"Unsupported value '{self}'")
+
+    @classmethod
+    def from_rs(cls, obj):
+        match obj:
+            case rustantic_test.MyUnitEnum.A:
+                return cls.A
+            case rustantic_test.MyUnitEnum.B:
+                return cls.B
+            case rustantic_test.MyUnitEnum.C:
+                return cls.C
+            case rustantic_test.MyUnitEnum.D:
+                return cls.D
+            case _:
+                raise ValueError(f"Unsupported value '{obj}'")
@@ -1,17 +1,23 @@
 # Generated by rustantic version: 0.2.0
 from pydantic import BaseModel, Field
-from pydantic import Field
-from uuid import UUID
 import rustantic_test
 
 class Nested(BaseModel):
     name: str
-    num: int = Field(..., ge=0)
-    id: UUID
+    num: int = Field(..., ge=0, le=4294967295)
+    id: int = Field(..., ge=0, le=18446744073709551615)
 
     def to_rs(self):
         return rustantic_test.Nested(
             name=self.name,
             num=self.num,
             id=self.id,
-        )
\ No newline at end of file
+        )
+
+    @classmethod
+    def from_rs(cls, obj):
+        return cls(
+            name=obj.name,
+            num=obj.num,
+            id=obj.id,
+        )
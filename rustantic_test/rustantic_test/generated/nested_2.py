@@ -1,8 +1,24 @@
 # Generated by rustantic version: 0.2.0
 from pydantic import BaseModel, Field
-from pydantic import Field
+from typing import Any, Optional
 import rustantic_test
 
 class Nested2(BaseModel):
-    name: str
-    num: int = Field(..., ge=0)
+    displayName: str = Field(..., description="Lowercase display name", min_length=3, pattern="^[a-z]+$")
+    num: int = Field(..., ge=1, le=100)
+    note: Optional[str] = None
+
+    def to_rs(self):
+        return rustantic_test.Nested2(
+            name=self.displayName,
+            num=self.num,
+            note=self.note,
+        )
+
+    @classmethod
+    def from_rs(cls, obj):
+        return cls(
+            displayName=obj.name,
+            num=obj.num,
+            note=obj.note,
+        )
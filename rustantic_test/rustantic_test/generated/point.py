@@ -0,0 +1,15 @@
+# Generated by rustantic version: 0.2.0
+from pydantic import BaseModel, ConfigDict, Field, RootModel
+from typing import Annotated
+import rustantic_test
+
+class Point(RootModel[tuple[Annotated[int, Field(ge=-2147483648, le=2147483647)], Annotated[int, Field(ge=0, le=65535)]]]):
+    model_config = ConfigDict(frozen=True)
+    root: tuple[Annotated[int, Field(ge=-2147483648, le=2147483647)], Annotated[int, Field(ge=0, le=65535)]]
+
+    def to_rs(self):
+        return rustantic_test.Point(self.root[0], self.root[1])
+
+    @classmethod
+    def from_rs(cls, obj):
+        return cls((obj._0, obj._1))
@@ -0,0 +1,52 @@
+# Generated by rustantic version: 0.2.0
+from pydantic import BaseModel, Field, RootModel
+from typing import Any, Literal, Union
+import enum
+import rustantic_test
+
+class ShapeDiscriminator(enum.Enum):
+    Circle = enum.auto()
+    Rect = enum.auto()
+
+
+class ShapeCircle(BaseModel):
+    kind: Literal[ShapeDiscriminator.Circle] = Field(default=ShapeDiscriminator.Circle, init=False, frozen=True)
+    r: float
+
+    def to_rs(self):
+        return rustantic_test.Shape.Circle(r=self.r)
+
+    @classmethod
+    def from_rs(cls, obj):
+        return cls(r=obj.r)
+
+
+class ShapeRect(BaseModel):
+    kind: Literal[ShapeDiscriminator.Rect] = Field(default=ShapeDiscriminator.Rect, init=False, frozen=True)
+    w: float = Field(..., gt=0)
+    h: float = Field(..., gt=0)
+
+    def to_rs(self):
+        return rustantic_test.Shape.Rect(w=self.w, h=self.h)
+
+    @classmethod
+    def from_rs(cls, obj):
+        return cls(w=obj.w, h=obj.h)
+
+
+ShapeType = Union[ShapeCircle,ShapeRect]
+
+class Shape(RootModel[ShapeType]):
+    root: ShapeType = Field(..., discriminator="kind")
+    def to_rs(self):
+        return self.root.to_rs()
+
+    @classmethod
+    def from_rs(cls, obj):
+        match obj:
+            case rustantic_test.Shape.Circle():
+                return cls(ShapeCircle.from_rs(obj))
+            case rustantic_test.Shape.Rect():
+                return cls(ShapeRect.from_rs(obj))
+            case _:
+                raise ValueError(f"Unsupported value '{obj}'")
@@ -0,0 +1,13 @@
+# Generated by rustantic version: 0.2.0
+from pydantic import BaseModel, Field, RootModel
+import rustantic_test
+
+class UserId(RootModel[int]):
+    root: int = Field(..., ge=1, le=18446744073709551615)
+
+    def to_rs(self):
+        return rustantic_test.UserId(self.root)
+
+    @classmethod
+    def from_rs(cls, obj):
+        return cls(obj._0)
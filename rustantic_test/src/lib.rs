@@ -1,30 +1,52 @@
 #![allow(dead_code)]
 use pyo3::prelude::*;
+use pyo3::types::PyDict;
 use rustantic_macros::pydantic;
-use uuid::Uuid;
 
-#[pydantic]
+#[pydantic(get_all)]
 #[derive(Clone, PartialEq)]
 pub struct Nested {
     pub name: String,
     pub num: u32,
-    pub id: Uuid,
+    pub id: u64,
 }
 
 #[pymethods]
 impl Nested {
     #[new]
-    pub fn new(name: String, num: u32, id: Uuid) -> PyResult<Self> {
-        Ok(Self { name, num, id: id })
+    pub fn new(name: String, num: u32, id: u64) -> PyResult<Self> {
+        Ok(Self { name, num, id })
     }
 }
-#[pydantic]
+#[pydantic(new, get_all)]
 #[derive(Clone, PartialEq)]
 pub struct Nested2 {
+    #[pydantic(
+        rename = "displayName",
+        description = "Lowercase display name",
+        min_length = 3,
+        pattern = "^[a-z]+$"
+    )]
     pub name: String,
+    #[pydantic(ge = 1, le = 100)]
     pub num: u32,
+    pub note: Option<String>,
+    #[pydantic(skip)]
+    pub cache: Vec<u8>,
 }
 
+#[pydantic(new, get_all)]
+#[derive(Clone, PartialEq)]
+pub struct UserId(#[pydantic(ge = 1)] u64);
+
+#[pydantic(new, frozen, get_all)]
+#[derive(Clone, PartialEq)]
+pub struct Point(i32, u16);
+
+#[pydantic(new, get_all)]
+#[derive(Clone, PartialEq)]
+pub struct Marker;
+
 #[pydantic]
 #[derive(Clone)]
 enum MyEnum {
@@ -33,6 +55,29 @@ enum MyEnum {
     C(i16),
 }
 
+#[pydantic]
+#[derive(Clone)]
+enum Shape {
+    Circle {
+        r: f64,
+    },
+    Rect {
+        #[pydantic(gt = 0)]
+        w: f64,
+        #[pydantic(gt = 0)]
+        h: f64,
+    },
+}
+
+// pyo3 doesn't support unit variants in complex enums, payload-less variants are declared as `Stop()`
+#[pydantic]
+#[derive(Clone)]
+enum Cmd {
+    Stop(),
+    Move(i32, i32),
+    Say(String),
+}
+
 #[pydantic]
 #[derive(Clone)]
 enum MyUnitEnum {
@@ -42,9 +87,10 @@ enum MyUnitEnum {
     D,
 }
 
-#[pydantic(aaa, bbb)]
+#[pydantic(frozen, extra = "forbid", get_all)]
 #[derive(Clone)]
 struct MyClass {
+    #[pydantic(description = "Name of the class")]
     pub name: String,
     pub num: u32,
     pub vec: Vec<f32>,
@@ -55,6 +101,7 @@ struct MyClass {
 #[pymethods]
 impl MyClass {
     #[new]
+    #[allow(clippy::too_many_arguments)]
     #[pyo3(signature = (name, num2, vec, nested, myenum, _nesteds, _opt1=None, _opt2=None, _opt3=None))]
     pub fn new(
         name: String,
@@ -77,12 +124,55 @@ impl MyClass {
     }
 }
 
+#[pydantic(get_all)]
+#[derive(Clone)]
+struct Job {
+    pub name: String,
+    pub retries: u8,
+    pub tags: Vec<String>,
+    pub verbose: bool,
+    pub label: String,
+    pub ratio: f64,
+}
+
+#[pymethods]
+impl Job {
+    #[new]
+    #[pyo3(signature = (name, /, retries=3, *tags, verbose=false, label="job".to_string(), ratio=-0.5, **options))]
+    pub fn new(
+        name: String,
+        retries: u8,
+        tags: Vec<String>,
+        verbose: bool,
+        label: String,
+        ratio: f64,
+        options: Option<&Bound<'_, PyDict>>,
+    ) -> Self {
+        let _ = options;
+        Self {
+            name,
+            retries,
+            tags,
+            verbose,
+            label,
+            ratio,
+        }
+    }
+}
+
 #[pymodule]
 fn rustantic_test(_: Python<'_>, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<Nested>()?;
+    m.add_class::<Nested2>()?;
+    m.add_class::<UserId>()?;
+    m.add_class::<Point>()?;
+    m.add_class::<Marker>()?;
     m.add_class::<MyClass>()?;
+    m.add_class::<Job>()?;
     m.add_class::<MyUnitEnum>()?;
     m.add_class::<MyEnum>()?;
+    m.add_class::<Shape>()?;
+    m.add_class::<Cmd>()?;
 
     Ok(())
 }